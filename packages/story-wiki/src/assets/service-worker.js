@@ -0,0 +1,193 @@
+// The service worker of StoryWiki, enabling reading offline.
+//
+// The app shell (the site itself, its script and wasm, styles, fonts, and
+// icons) is precached on install and cached at runtime as it is requested. Pages are cached as they
+// are read, or all at once when a reader makes a continuity available offline.
+// Everything is fetched from the network first, falling back to the cache, so
+// readers always see the latest content when online.
+//
+// When `manifest.toml` changes, cached pages it no longer lists are removed, as
+// they have been moved or rewritten. Pages still listed are kept, so
+// continuities made available offline stay available.
+
+const SHELL_CACHE = "story-wiki-shell-v1";
+const CONTENT_CACHE = "story-wiki-content-v1";
+const CACHES = [SHELL_CACHE, CONTENT_CACHE];
+
+const MANIFEST_URL = new URL("manifest.toml", self.registration.scope).href;
+const INDEX_URL = new URL("index.html", self.registration.scope).href;
+
+// Extensions of the files the site itself is built from
+const SITE_EXTENSIONS = [".js", ".wasm"];
+
+const SHELL = [
+    "./",
+    "index.html",
+    "manifest.toml",
+    "style.css",
+    "manifest.webmanifest",
+    "favicon.ico",
+    "icon.svg",
+    "charter_regular.woff2",
+    "charter_italic.woff2",
+    "charter_bold.woff2",
+    "charter_bold_italic.woff2",
+    "firasans_regular.woff2",
+    "sourcecodepro_regular.woff2",
+];
+
+self.addEventListener("install", (event) => {
+    event.waitUntil(precacheShell().then(() => self.skipWaiting()));
+});
+
+self.addEventListener("activate", (event) => {
+    event.waitUntil(
+        caches.keys()
+            .then((keys) => Promise.all(
+                keys.filter((key) => !CACHES.includes(key)).map((key) => caches.delete(key))
+            ))
+            .then(() => self.clients.claim())
+    );
+});
+
+self.addEventListener("fetch", (event) => {
+    const request = event.request;
+    if (request.method !== "GET" || new URL(request.url).origin !== self.location.origin) {
+        return;
+    }
+    if (request.mode === "navigate") {
+        // Every route of the site is served by `index.html`
+        event.respondWith(networkFirst(request, SHELL_CACHE, INDEX_URL));
+    } else if (request.url.split("?")[0] === MANIFEST_URL) {
+        event.respondWith(fetchManifest(request));
+    } else {
+        event.respondWith(networkFirst(request, cacheFor(request)));
+    }
+});
+
+self.addEventListener("message", (event) => {
+    if (event.data && event.data.type === "cache-pages") {
+        event.waitUntil(cachePages(event.data.urls));
+    }
+});
+
+// The cache a request belongs in. The script and wasm of the site are fetched
+// the same way as pages and bundles, but must never be cleared with them or the
+// site could not start offline.
+function cacheFor(request) {
+    const path = new URL(request.url).pathname;
+    if (SITE_EXTENSIONS.some((extension) => path.endsWith(extension))) {
+        return SHELL_CACHE;
+    }
+    // Other requests made by the site itself with `fetch` are for pages and
+    // bundles
+    return request.destination === "" ? CONTENT_CACHE : SHELL_CACHE;
+}
+
+// Caches the app shell. Trunk names the script and wasm of the site by their
+// hash, so they are found by reading `index.html`.
+async function precacheShell() {
+    const cache = await caches.open(SHELL_CACHE);
+    // Optional files such as fonts may be missing, which should not stop the
+    // rest of the shell from being cached
+    await Promise.all(SHELL.map((url) => cache.add(url).catch(() => {})));
+    const index = await cache.match(INDEX_URL);
+    if (!index) {
+        return;
+    }
+    const html = await index.text();
+    const site = new Set();
+    for (const [, url] of html.matchAll(/["']([^"'\s]+\.(?:js|wasm))["']/g)) {
+        site.add(new URL(url, self.registration.scope).href);
+    }
+    site.delete(new URL("service-worker.js", self.registration.scope).href);
+    // The site cannot start without these, so installing fails if they do
+    await cache.addAll([...site]);
+}
+
+// Fetches from the network, caching the response, or falls back to the cache
+// if the network is unavailable.
+async function networkFirst(request, cacheName, fallbackUrl) {
+    const cache = await caches.open(cacheName);
+    try {
+        const response = await fetch(request);
+        if (response.ok) {
+            await cache.put(fallbackUrl || request, response.clone());
+        }
+        return response;
+    } catch (error) {
+        const cached = await cache.match(fallbackUrl || request);
+        if (cached) {
+            return cached;
+        }
+        throw error;
+    }
+}
+
+// Fetches the manifest like any other shell file, removing cached pages it no
+// longer lists if it has changed since it was last cached.
+async function fetchManifest(request) {
+    const cache = await caches.open(SHELL_CACHE);
+    try {
+        const response = await fetch(request);
+        if (response.ok) {
+            const cached = await cache.match(MANIFEST_URL);
+            const previous = cached ? await cached.text() : null;
+            const current = await response.clone().text();
+            if (previous !== null && previous !== current) {
+                await pruneContent(current);
+            }
+            await cache.put(MANIFEST_URL, response.clone());
+        }
+        return response;
+    } catch (error) {
+        const cached = await cache.match(MANIFEST_URL);
+        if (cached) {
+            return cached;
+        }
+        throw error;
+    }
+}
+
+// Removes every cached page and bundle the manifest no longer lists.
+async function pruneContent(manifest) {
+    const cache = await caches.open(CONTENT_CACHE);
+    const requests = await cache.keys();
+    await Promise.all(requests
+        .filter((request) => !isListed(request.url, manifest))
+        .map((request) => cache.delete(request)));
+}
+
+// If the manifest lists a URL, by its path from the site root, and by the
+// content hash in its `v` query if it has one. The manifest is not parsed, the
+// path and hash only need to appear in it as strings. A page without a
+// `content_hash` is kept while its path is listed, so when offline it may be
+// older than the manifest.
+function isListed(url, manifest) {
+    const scope = new URL(self.registration.scope).pathname;
+    const parsed = new URL(url);
+    const pathname = decodeURI(parsed.pathname);
+    const path = pathname.startsWith(scope) ? pathname.slice(scope.length) : pathname;
+    const hash = parsed.searchParams.get("v");
+    return mentions(manifest, path) && (hash === null || mentions(manifest, hash));
+}
+
+// If a value appears in the manifest as a TOML string
+function mentions(manifest, value) {
+    return manifest.includes(`"${value}"`) || manifest.includes(`'${value}'`);
+}
+
+// Caches every page, so they can be read offline.
+async function cachePages(urls) {
+    const cache = await caches.open(CONTENT_CACHE);
+    await Promise.all(urls.map(async (url) => {
+        try {
+            const response = await fetch(url);
+            if (response.ok) {
+                await cache.put(url, response);
+            }
+        } catch (error) {
+            console.warn(`Unable to cache ${url} for offline reading`, error);
+        }
+    }));
+}
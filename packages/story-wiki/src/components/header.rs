@@ -3,8 +3,13 @@ use yew::prelude::*;
 use yew_router::prelude::*;
 use yew_router::hooks::{use_location, use_navigator};
 
+use crate::components::peek::{PeekBanner, PeekToggle};
+use crate::components::picker::chapter_picker::ChapterPicker;
 use crate::components::picker::continuity_picker::ContinuityPicker;
+use crate::components::picker::optional_release_picker::OptionalReleasePicker;
+use crate::components::picker::profile_picker::ProfilePicker;
 use crate::components::picker::release_picker::ReleasePicker;
+use crate::components::settings::Settings;
 use crate::hooks::continuity_switcher::use_active_continuity;
 use crate::routes::search::SearchQuery;
 use crate::routes::Route;
@@ -83,6 +88,9 @@ pub fn Header() -> Html {
             }
             if manifest.has_multiple_releases() {
                 <ReleasePicker />
+                <ChapterPicker />
+                <PeekToggle />
+                <OptionalReleasePicker />
             }
             <Link<Route> 
                 classes="categories"
@@ -94,6 +102,9 @@ pub fn Header() -> Html {
             >
                 {"Categories"}
             </Link<Route>>
+            <ProfilePicker />
+            <Settings />
+            <PeekBanner />
         </header>
     }
 }
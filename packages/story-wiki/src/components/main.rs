@@ -3,10 +3,12 @@ use yew_router::prelude::*;
 
 use crate::components::footer::Footer;
 use crate::components::header::Header;
+use crate::components::preview_banner::PreviewBanner;
 use crate::components::set_title::SetTitle;
 use crate::hooks::continuity_switcher::use_active_continuity;
+use crate::hooks::hot_reload::use_manifest_hot_reload;
 use crate::routes::{switch, Route};
-use crate::states::manifest::use_manifest;
+use crate::states::manifest::{retry_manifest, use_manifest};
 use crate::states::title::use_title_switcher;
 
 /// The entry point for StoryWiki, intended to be attached to the body of a website.
@@ -14,14 +16,28 @@ use crate::states::title::use_title_switcher;
 pub fn Main() -> Html {
     log::trace!("Rendering Main");
     let manifest = use_manifest();
+    use_manifest_hot_reload();
+
+    let failed = manifest.failed().map(|e| {
+        let onclick = Callback::from(|_: MouseEvent| retry_manifest());
+        html! {
+            <main class="download-failed">
+                <p>{ format!("Couldn't load this wiki, {e}.") }</p>
+                <button {onclick}>{"Retry"}</button>
+            </main>
+        }
+    });
+
     html! {
         <BrowserRouter>
             if manifest.is_ready() {
                 <SetTitle />
+                <PreviewBanner />
                 <Header />
                 <MainInner />
                 <Footer />
             }
+            { for failed }
         </BrowserRouter>
     }
 }
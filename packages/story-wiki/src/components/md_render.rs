@@ -11,6 +11,7 @@ use crate::states::active_release::use_active_release_tracker;
 use crate::states::manifest::{use_manifest, Manifest};
 use crate::states::release_citations::use_release_citations;
 use crate::try_html;
+use crate::utils::cond::Observed;
 use crate::utils::irc::Irc;
 
 #[derive(PartialEq, Properties)]
@@ -22,10 +23,11 @@ pub struct MdRenderProps {
 #[derive(Debug)]
 struct MdRenderer<'a, I> {
     iter: I,
-    observed_tags: HashSet<&'a str>,
+    observed_tags: Observed<'a>,
     release_citations: bool,
     manifest: Irc<Manifest>,
     continuity_reference: &'a str,
+    opted_in_content_warnings: HashSet<String>,
 
     table_alignments: Vec<Alignment>,
     table_in_body: bool,
@@ -42,10 +44,11 @@ where
 {
     fn new(
         iter: I,
-        observed_tags: HashSet<&'a str>,
+        observed_tags: Observed<'a>,
         release_citations: bool,
         manifest: Irc<Manifest>,
         continuity_reference: &'a str,
+        opted_in_content_warnings: HashSet<String>,
     ) -> Self {
         MdRenderer {
             iter,
@@ -53,6 +56,7 @@ where
             release_citations,
             manifest,
             continuity_reference,
+            opted_in_content_warnings,
             table_alignments: Default::default(),
             table_in_body: Default::default(),
             table_cell_index: Default::default(),
@@ -183,6 +187,28 @@ where
         }
     }
 
+    /// Opens a block behind a content warning, which is collapsed unless the
+    /// reader has opted in to seeing that kind of content without a warning
+    fn start_content_warning(&mut self, kind: String) {
+        if self.opted_in_content_warnings.contains(&kind) {
+            let mut div_tag = VTag::new("div");
+            div_tag.add_attribute("class", "content-warning");
+            div_tag.add_attribute("data-kind", kind);
+            self.nest_tag(div_tag);
+        } else {
+            let display_name = self.manifest.content_warning_display_name(&kind);
+            let mut summary_tag = VTag::new("summary");
+            summary_tag.add_child(VNode::VText(VText::new(format!(
+                "Content warning: {display_name}"
+            ))));
+            let mut details_tag = VTag::new("details");
+            details_tag.add_attribute("class", "content-warning");
+            details_tag.add_attribute("data-kind", kind);
+            details_tag.add_child(VNode::VTag(Box::new(summary_tag)));
+            self.nest_tag(details_tag);
+        }
+    }
+
     fn nest_tag(&mut self, tag: VTag) {
         self.tag_buf.push(tag);
         self.hard_collapse = false;
@@ -275,6 +301,15 @@ where
             match reader.read_event(&mut buf) {
                 Ok(event) => {
                     match event {
+                        XmlEvent::Start(start) if start.name() == b"content-warning" => {
+                            let kind = start
+                                .attributes()
+                                .flatten()
+                                .find(|attribute| attribute.key == b"kind")
+                                .map(|attribute| to_string(&attribute.value))
+                                .unwrap_or_default();
+                            self.start_content_warning(kind);
+                        }
                         XmlEvent::Start(start) => {
                             let name = to_string(start.name());
                             let mut vtag = VTag::new(name);
@@ -304,7 +339,7 @@ where
                             }
                             let should_show = if name_bytes.starts_with(b"x-") {
                                 let release = to_string(&name_bytes[2..]);
-                                if self.observed_tags.contains(&release.as_str()) {
+                                if self.observed_tags.releases.contains(&release.as_str()) {
                                     if self.release_citations {
                                         ShouldShow::CiteHide(release)
                                     } else {
@@ -315,7 +350,7 @@ where
                                 }
                             } else if name_bytes.starts_with(b"o-") {
                                 let release = to_string(&name_bytes[2..]);
-                                if self.observed_tags.contains(&release.as_str()) {
+                                if self.observed_tags.releases.contains(&release.as_str()) {
                                     if self.release_citations {
                                         ShouldShow::CiteFrom(release)
                                     } else {
@@ -324,6 +359,20 @@ where
                                 } else {
                                     ShouldShow::None
                                 }
+                            } else if name_bytes.starts_with(b"f-") {
+                                let flag = to_string(&name_bytes[2..]);
+                                if self.observed_tags.flags.contains(&flag.as_str()) {
+                                    ShouldShow::Flatten
+                                } else {
+                                    ShouldShow::None
+                                }
+                            } else if name_bytes.starts_with(b"nf-") {
+                                let flag = to_string(&name_bytes[3..]);
+                                if self.observed_tags.flags.contains(&flag.as_str()) {
+                                    ShouldShow::None
+                                } else {
+                                    ShouldShow::Flatten
+                                }
                             } else {
                                 ShouldShow::AsIs
                             };
@@ -484,6 +533,7 @@ pub fn MdRender(props: &MdRenderProps) -> Html {
         release_citations,
         manifest.clone(),
         &props.continuity,
+        active_release_tracker.opted_in_content_warnings().clone(),
     );
     render.run();
 
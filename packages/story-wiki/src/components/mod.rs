@@ -3,5 +3,9 @@ pub mod header;
 pub mod main;
 pub mod md_render;
 pub mod page_render;
+pub mod peek;
 pub mod picker;
+pub mod preview_banner;
+pub mod progress_transfer;
 pub mod set_title;
+pub mod settings;
@@ -1,22 +1,48 @@
 use yew::prelude::*;
 
 use crate::components::md_render::MdRender;
-use crate::states::pages::use_page;
+use crate::hooks::hot_reload::use_page_hot_reload;
+use crate::hooks::prefetch::use_link_prefetch;
+use crate::states::pages::{retry_page, use_page};
 use crate::utils::downloadable_resource::DownloadableResource;
 
 #[derive(PartialEq, Properties)]
 pub struct PageRenderProps {
     pub resource_path: String,
+    #[prop_or_default]
+    pub content_hash: Option<String>,
+    #[prop_or_default]
+    pub bundle: Option<String>,
     pub continuity: String,
 }
 
 #[function_component]
 pub fn PageRender(props: &PageRenderProps) -> Html {
     log::trace!("Rendering PageRender {}", props.resource_path);
-    let page = use_page(&props.resource_path);
-    if let DownloadableResource::Ready(page) = page {
-        html! { <MdRender content={String::clone(&page)} continuity={props.continuity.clone()} /> }
-    } else {
-        html! {}
+    let page = use_page(
+        &props.resource_path,
+        props.content_hash.as_deref(),
+        props.bundle.as_deref(),
+    );
+    use_link_prefetch(page.is_ready());
+    use_page_hot_reload(&props.resource_path, props.content_hash.as_deref());
+    match page {
+        DownloadableResource::Ready(page) => {
+            html! { <MdRender content={String::clone(&page)} continuity={props.continuity.clone()} /> }
+        }
+        DownloadableResource::Failed(e) => {
+            let resource_path = props.resource_path.clone();
+            let content_hash = props.content_hash.clone();
+            let onclick = Callback::from(move |_: MouseEvent| {
+                retry_page(&resource_path, content_hash.as_deref())
+            });
+            html! {
+                <div class="download-failed">
+                    <p>{ format!("Couldn't load this page, {e}.") }</p>
+                    <button {onclick}>{"Retry"}</button>
+                </div>
+            }
+        }
+        _ => html! {},
     }
 }
@@ -0,0 +1,65 @@
+use yew::prelude::*;
+
+use crate::states::active_release::{use_active_release_switcher, use_active_release_tracker};
+use crate::states::manifest::use_manifest;
+use crate::try_html;
+
+/// Renders a toggle for if picking a release should peek ahead at it, rather
+/// than save it as progress.
+#[function_component]
+pub fn PeekToggle() -> Html {
+    log::trace!("Rendering PeekToggle");
+
+    let active_release = use_active_release_tracker();
+    let active_release_switcher = use_active_release_switcher();
+
+    let checked = active_release.peek_enabled();
+    let onchange =
+        Callback::from(move |_: Event| active_release_switcher.set_peek_enabled(!checked));
+
+    html! {
+        <label class="peek-toggle">
+            <input type="checkbox" checked={checked} {onchange} />
+            {"Peek"}
+        </label>
+    }
+}
+
+/// Renders a warning while peeking ahead, as content is not being shown at
+/// the readers saved progress.
+#[function_component]
+pub fn PeekBanner() -> Html {
+    log::trace!("Rendering PeekBanner");
+
+    let manifest = use_manifest();
+    let active_release = use_active_release_tracker();
+    let active_release_switcher = use_active_release_switcher();
+
+    if !active_release.peek_enabled() {
+        return html! {};
+    }
+
+    let manifest = try_html!(manifest.opt());
+
+    let peeked_at = active_release
+        .peeked_releases()
+        .filter_map(|(continuity_reference, release_reference)| {
+            manifest.release(continuity_reference, release_reference)
+        })
+        .map(|release| release.display_name())
+        .collect::<Vec<_>>();
+
+    let onclick =
+        Callback::from(move |_: MouseEvent| active_release_switcher.set_peek_enabled(false));
+
+    html! {
+        <aside class="peek-banner">
+            if peeked_at.is_empty() {
+                {"Pick a release to peek ahead at it without changing your saved progress."}
+            } else {
+                { format!("Peeking ahead at {}, your saved progress has not changed.", peeked_at.join(", ")) }
+            }
+            <button {onclick}>{"Stop peeking"}</button>
+        </aside>
+    }
+}
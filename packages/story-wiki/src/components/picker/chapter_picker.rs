@@ -0,0 +1,71 @@
+use std::iter::once;
+
+use yew::prelude::*;
+
+use crate::components::picker::{OptionSegment, Picker, PickerFeed};
+use crate::hooks::continuity_switcher::use_active_continuity;
+use crate::states::active_release::{use_active_release_switcher, use_active_release_tracker};
+use crate::states::manifest::use_manifest;
+use crate::try_html;
+
+/// Renders a picker for the chapter of the active release the reader is up to,
+/// if the active release has chapters and is the readers saved progress.
+#[function_component]
+pub fn ChapterPicker() -> Html {
+    log::trace!("Rendering ChapterPicker");
+
+    let manifest = use_manifest();
+    let active_release = use_active_release_tracker();
+    let active_release_switcher = use_active_release_switcher();
+    let active_continuity = use_active_continuity();
+
+    let manifest = try_html!(manifest.opt());
+    let continuity = try_html!(active_continuity.active());
+    // Chapters are part of the saved progress, so cannot be picked while
+    // peeking at or previewing another release
+    let active = active_release.active(continuity.reference_name());
+    if active != active_release.own(continuity.reference_name()) {
+        return html! {};
+    }
+    let release = try_html!(
+        active.and_then(|release| manifest.release(continuity.reference_name(), release))
+    );
+
+    if release.chapters().is_empty() {
+        return html! {};
+    }
+
+    let active_chapter = active_release
+        .active_chapter(continuity.reference_name())
+        .filter(|chapter| release.chapter(chapter).is_some());
+
+    let options = once(PickerFeed {
+        display: "Finished".to_string(),
+        hidden: false,
+        disabled: false,
+        new_group: None,
+        selected: active_chapter.is_none(),
+        value: String::new(),
+    })
+    .chain(release.chapters().iter().map(|chapter| PickerFeed {
+        display: chapter.display_name().to_string(),
+        hidden: false,
+        disabled: false,
+        new_group: None,
+        selected: Some(chapter.reference_name()) == active_chapter,
+        value: chapter.reference_name().to_string(),
+    }))
+    .collect::<Vec<OptionSegment>>();
+
+    let continuity_reference = continuity.reference_name().to_string();
+    let onpick = Callback::from(move |chapter: String| {
+        active_release_switcher.switch_chapter(
+            continuity_reference.clone(),
+            Some(chapter).filter(|chapter| !chapter.is_empty()),
+        )
+    });
+
+    html! {
+        <Picker {options} {onpick} />
+    }
+}
@@ -23,6 +23,7 @@ pub fn ContinuityPicker() -> Html {
         .map(|continuity| PickerFeed {
             display: continuity.display_name().to_string(),
             hidden: false,
+            disabled: false,
             new_group: None,
             selected: Some(continuity) == active_continuity,
             value: continuity.reference_name().to_string(),
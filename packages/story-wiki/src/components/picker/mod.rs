@@ -1,7 +1,10 @@
 use web_sys::HtmlSelectElement;
 use yew::prelude::*;
 
+pub mod chapter_picker;
 pub mod continuity_picker;
+pub mod optional_release_picker;
+pub mod profile_picker;
 pub mod release_picker;
 
 #[derive(PartialEq, Debug)]
@@ -36,6 +39,7 @@ pub struct SingleOption {
     pub display: String,
     pub selected: bool,
     pub hidden: bool,
+    pub disabled: bool,
 }
 impl SingleOption {
     fn html(&self) -> Html {
@@ -44,6 +48,7 @@ impl SingleOption {
                 value={self.value.to_string()}
                 selected={self.selected}
                 hidden={self.hidden}
+                disabled={self.disabled}
             >
                 {&self.display}
             </option>
@@ -60,6 +65,8 @@ pub struct PickerFeed {
     pub selected: bool,
     /// True if this option is to be hidden, contributing width to the element by not content
     pub hidden: bool,
+    /// True if this option is shown but cannot be picked
+    pub disabled: bool,
     /// None to continue previous group, Some(None) to exit group, Some(Some(v)) to start group v
     pub new_group: Option<Option<String>>,
 }
@@ -74,6 +81,7 @@ impl FromIterator<PickerFeed> for Vec<OptionSegment> {
                      value,
                      display,
                      hidden,
+                     disabled,
                      selected,
                      new_group,
                  }| {
@@ -82,6 +90,7 @@ impl FromIterator<PickerFeed> for Vec<OptionSegment> {
                         display,
                         selected,
                         hidden,
+                        disabled,
                     };
                     match (vec.last_mut(), new_group) {
                         (Some(OptionSegment::OptionGroup { options, .. }), None) => {
@@ -0,0 +1,64 @@
+use yew::prelude::*;
+
+use crate::hooks::continuity_switcher::use_active_continuity;
+use crate::states::active_release::{use_active_release_switcher, use_active_release_tracker};
+use crate::states::manifest::use_manifest;
+use crate::try_html;
+
+/// Renders a checkbox for each optional release of the active continuity that
+/// can be observed on the active release.
+#[function_component]
+pub fn OptionalReleasePicker() -> Html {
+    log::trace!("Rendering OptionalReleasePicker");
+
+    let manifest = use_manifest();
+    let active_release = use_active_release_tracker();
+    let active_release_switcher = use_active_release_switcher();
+    let active_continuity = use_active_continuity();
+
+    let manifest = try_html!(manifest.opt());
+    let continuity = try_html!(active_continuity.active());
+
+    let observed_releases_references = active_release.observed_releases_references(&manifest);
+
+    let options = manifest
+        .optional_releases(continuity.reference_name())
+        .filter(|release| {
+            release.after().map_or(true, |after| {
+                observed_releases_references.releases.contains(after)
+            })
+        })
+        .map(|release| {
+            let checked = active_release
+                .optional_observed(continuity.reference_name(), release.reference_name());
+            let onchange = {
+                let active_release_switcher = active_release_switcher.clone();
+                let continuity_reference = continuity.reference_name().to_string();
+                let release_reference = release.reference_name().to_string();
+                Callback::from(move |_: Event| {
+                    active_release_switcher.set_optional_observed(
+                        continuity_reference.clone(),
+                        release_reference.clone(),
+                        !checked,
+                    )
+                })
+            };
+            html! {
+                <label>
+                    <input type="checkbox" checked={checked} {onchange} />
+                    { release.display_name() }
+                </label>
+            }
+        })
+        .collect::<Vec<_>>();
+
+    if options.is_empty() {
+        return html! {};
+    }
+
+    html! {
+        <fieldset class="optional-releases">
+            { for options }
+        </fieldset>
+    }
+}
@@ -0,0 +1,49 @@
+use yew::prelude::*;
+
+use crate::components::picker::{OptionSegment, Picker, PickerFeed};
+use crate::states::active_release::{use_active_release_switcher, use_active_release_tracker};
+
+/// Renders a picker for the reader profile in use, and a button to create a new
+/// profile.
+#[function_component]
+pub fn ProfilePicker() -> Html {
+    log::trace!("Rendering ProfilePicker");
+
+    let active_release = use_active_release_tracker();
+    let active_release_switcher = use_active_release_switcher();
+
+    let options = active_release
+        .profiles()
+        .map(|profile| PickerFeed {
+            display: profile.to_string(),
+            hidden: false,
+            disabled: false,
+            new_group: None,
+            selected: profile == active_release.active_profile(),
+            value: profile.to_string(),
+        })
+        .collect::<Vec<OptionSegment>>();
+
+    let onpick = {
+        let active_release_switcher = active_release_switcher.clone();
+        Callback::from(move |profile: String| active_release_switcher.switch_profile(profile))
+    };
+
+    let onclick = Callback::from(move |_: MouseEvent| {
+        let profile = web_sys::window()
+            .and_then(|window| window.prompt_with_message("Name of the new profile").ok())
+            .flatten()
+            .map(|profile| profile.trim().to_string())
+            .filter(|profile| !profile.is_empty());
+        if let Some(profile) = profile {
+            active_release_switcher.switch_profile(profile)
+        }
+    });
+
+    html! {
+        <div class="profile-picker">
+            <Picker {options} {onpick} />
+            <button {onclick} title="New profile">{"+"}</button>
+        </div>
+    }
+}
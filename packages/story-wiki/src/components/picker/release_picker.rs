@@ -14,31 +14,75 @@ pub fn ReleasePicker() -> Html {
     let active_release_switcher = use_active_release_switcher();
     let active_continuity = use_active_continuity();
 
+    let peek_enabled = active_release.peek_enabled();
     let active_continuity = active_continuity.active();
     let active_release =
         active_continuity.and_then(|ac| active_release.active(ac.reference_name()));
 
+    let manifest = manifest.opt();
+    let mut previous_branch = None;
+    // The group main line releases are in, to return to after a branch
+    let mut trunk_group = None::<String>;
     let options = manifest
-        .opt()
         .as_deref()
         .map(|m| m.all_releases())
         .into_iter()
         .flatten()
-        .map(|(release, continuity)| PickerFeed {
-            display: release.display_name().to_string(),
-            hidden: Some(continuity) != active_continuity,
-            new_group: release
-                .begins_group()
-                .map(|inner| inner.map(|inner| inner.to_string())),
-            selected: Some(release.reference_name()) == active_release,
-            value: release.reference_name().to_string(),
+        .filter(|(release, _)| !release.optional())
+        .map(|(release, continuity)| {
+            let branch = release
+                .branch()
+                .map(|branch| (continuity.reference_name(), branch));
+            let begins_branch = branch.is_some() && branch != previous_branch;
+            let ends_branch = branch.is_none() && previous_branch.is_some();
+            previous_branch = branch;
+            let new_group = match branch {
+                Some((continuity_reference, branch)) if begins_branch => {
+                    Some(manifest.as_deref().map(|m| {
+                        m.branch_display_name(continuity_reference, branch)
+                            .to_string()
+                    }))
+                }
+                _ => {
+                    let begins_group = release
+                        .begins_group()
+                        .map(|inner| inner.map(|inner| inner.to_string()));
+                    if let (None, Some(group)) = (branch, &begins_group) {
+                        trunk_group = group.clone();
+                    }
+                    match begins_group {
+                        None if ends_branch => Some(trunk_group.clone()),
+                        begins_group => begins_group,
+                    }
+                }
+            };
+            let available = release.is_available();
+            let display = match release.available_from() {
+                Some(available_from) if !available && release.show_countdown() => {
+                    format!("{} ({})", release.display_name(), countdown(available_from))
+                }
+                _ => release.display_name().to_string(),
+            };
+            PickerFeed {
+                display,
+                hidden: Some(continuity) != active_continuity
+                    || (!available && release.hide_until_available()),
+                disabled: !available,
+                new_group,
+                selected: Some(release.reference_name()) == active_release,
+                value: release.reference_name().to_string(),
+            }
         })
         .collect::<Vec<OptionSegment>>();
 
     let active_continuity = active_continuity.map(|c| c.reference_name().to_string());
     let onpick = Callback::from(move |new_release: String| {
         if let Some(active_continuity) = active_continuity.clone() {
-            active_release_switcher.switch(active_continuity, new_release)
+            if peek_enabled {
+                active_release_switcher.peek(active_continuity, new_release)
+            } else {
+                active_release_switcher.switch(active_continuity, new_release)
+            }
         }
     });
 
@@ -46,3 +90,14 @@ pub fn ReleasePicker() -> Html {
         <Picker {options} {onpick} />
     }
 }
+
+/// Describes how long until the given time, in milliseconds since the unix
+/// epoch, i.e. `in 3 days`
+fn countdown(available_from: f64) -> String {
+    let days = ((available_from - js_sys::Date::now()) / 86_400_000.0).ceil() as i64;
+    if days <= 1 {
+        "in 1 day".to_string()
+    } else {
+        format!("in {days} days")
+    }
+}
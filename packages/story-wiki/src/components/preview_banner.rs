@@ -0,0 +1,69 @@
+use yew::prelude::*;
+use yew_router::hooks::{use_navigator, use_route};
+
+use crate::routes::Route;
+use crate::states::active_release::{use_active_release_switcher, use_active_release_tracker};
+use crate::states::manifest::use_manifest;
+use crate::try_html;
+
+/// Renders a banner while previewing a link shared at someone elses progress,
+/// with the option to adopt that progress.
+#[function_component]
+pub fn PreviewBanner() -> Html {
+    log::trace!("Rendering PreviewBanner");
+
+    let manifest = use_manifest();
+    let active_release = use_active_release_tracker();
+    let active_release_switcher = use_active_release_switcher();
+    let navigator = use_navigator();
+    let route = use_route::<Route>();
+
+    if !active_release.is_previewing() {
+        return html! {};
+    }
+
+    let manifest = try_html!(manifest.opt());
+    let navigator = try_html!(navigator);
+    let route = try_html!(route);
+
+    let shared_releases = active_release
+        .shared_releases()
+        .map(|(continuity_reference, release_reference)| {
+            (
+                continuity_reference.to_string(),
+                release_reference.to_string(),
+            )
+        })
+        .collect::<Vec<_>>();
+    let shared_at = shared_releases
+        .iter()
+        .filter_map(|(continuity_reference, release_reference)| {
+            manifest.release(continuity_reference, release_reference)
+        })
+        .map(|release| release.display_name())
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let onadopt = {
+        let navigator = navigator.clone();
+        let route = route.clone();
+        Callback::from(move |_: MouseEvent| {
+            for (continuity_reference, release_reference) in shared_releases.clone() {
+                active_release_switcher.switch(continuity_reference, release_reference);
+            }
+            navigator.replace(route.clone());
+        })
+    };
+    let ondismiss = Callback::from(move |_: MouseEvent| navigator.replace(route.clone()));
+
+    html! {
+        <aside class="preview-banner">
+            <p>
+                { format!("This link was shared at {shared_at}. ") }
+                {"It is shown at that progress, or yours if you are not as far along."}
+            </p>
+            <button onclick={onadopt}>{"Adopt this progress"}</button>
+            <button onclick={ondismiss}>{"Dismiss"}</button>
+        </aside>
+    }
+}
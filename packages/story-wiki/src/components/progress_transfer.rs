@@ -0,0 +1,137 @@
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::{spawn_local, JsFuture};
+use web_sys::{HtmlElement, HtmlInputElement, HtmlTextAreaElement};
+use yew::prelude::*;
+
+use crate::states::active_release::use_active_release_switcher;
+
+/// Downloads text as a file, as if the reader had followed a link to it
+fn download_file(file_name: &str, content: &str) -> Result<(), String> {
+    let document = web_sys::window()
+        .and_then(|window| window.document())
+        .ok_or("The document is not available")?;
+    let link = document
+        .create_element("a")
+        .map_err(|_| "Unable to create the download link")?;
+    let href = format!(
+        "data:application/json;charset=utf-8,{}",
+        js_sys::encode_uri_component(content)
+    );
+    link.set_attribute("href", &href)
+        .and_then(|_| link.set_attribute("download", file_name))
+        .map_err(|_| "Unable to create the download link")?;
+    link.dyn_into::<HtmlElement>()
+        .map_err(|_| "Unable to create the download link")?
+        .click();
+    Ok(())
+}
+
+/// Renders the export of the readers progress as a code and a JSON file, and
+/// the import of either.
+#[function_component]
+pub fn ProgressTransfer() -> Html {
+    log::trace!("Rendering ProgressTransfer");
+
+    let active_release_switcher = use_active_release_switcher();
+    let import_node_ref = use_node_ref();
+    let export = use_state(|| None::<Result<String, String>>);
+    let report = use_state(|| None::<Result<Vec<String>, String>>);
+
+    // Exporting is left until asked for, as this is rendered whenever the
+    // header is, even while the settings are closed
+    let oncode = {
+        let active_release_switcher = active_release_switcher.clone();
+        let export = export.clone();
+        Callback::from(move |_: MouseEvent| export.set(Some(active_release_switcher.export_code())))
+    };
+
+    let ondownload = {
+        let active_release_switcher = active_release_switcher.clone();
+        let export = export.clone();
+        Callback::from(move |_: MouseEvent| {
+            let downloaded = active_release_switcher
+                .export_json()
+                .and_then(|json| download_file("story-wiki-progress.json", &json));
+            if let Err(e) = downloaded {
+                export.set(Some(Err(e)));
+            }
+        })
+    };
+
+    let onimport = {
+        let active_release_switcher = active_release_switcher.clone();
+        let import_node_ref = import_node_ref.clone();
+        let report = report.clone();
+        Callback::from(move |_: MouseEvent| {
+            let exported = import_node_ref
+                .cast::<HtmlTextAreaElement>()
+                .unwrap()
+                .value();
+            report.set(Some(active_release_switcher.import(&exported)));
+        })
+    };
+
+    let onfile = {
+        let report = report.clone();
+        Callback::from(move |e: Event| {
+            let file = e
+                .target_unchecked_into::<HtmlInputElement>()
+                .files()
+                .and_then(|files| files.get(0));
+            if let Some(file) = file {
+                let active_release_switcher = active_release_switcher.clone();
+                let report = report.clone();
+                spawn_local(async move {
+                    let text = JsFuture::from(file.text()).await;
+                    match text.ok().and_then(|text| text.as_string()) {
+                        Some(exported) => {
+                            report.set(Some(active_release_switcher.import(&exported)))
+                        }
+                        None => report.set(Some(Err("Unable to read the file".to_string()))),
+                    }
+                });
+            }
+        })
+    };
+
+    let export = match &*export {
+        None => html! {},
+        Some(Ok(code)) => html! {
+            <label>
+                {"Progress code"}
+                <input type="text" readonly=true value={code.clone()} />
+            </label>
+        },
+        Some(Err(e)) => html! { <p>{ format!("Unable to export progress: {e}") }</p> },
+    };
+
+    let report = match &*report {
+        None => html! {},
+        Some(Ok(removed)) if removed.is_empty() => html! { <p>{"Progress imported."}</p> },
+        Some(Ok(removed)) => html! {
+            <>
+                <p>{"Progress imported, but these do not exist in this wiki and were left out:"}</p>
+                <ul>
+                    { for removed.iter().map(|removed| html! { <li>{ removed }</li> }) }
+                </ul>
+            </>
+        },
+        Some(Err(e)) => html! { <p>{ format!("Unable to import progress: {e}") }</p> },
+    };
+
+    html! {
+        <fieldset class="progress-transfer">
+            <legend>{"Move progress to another device"}</legend>
+            <button onclick={oncode}>{"Show progress code"}</button>
+            <button onclick={ondownload}>{"Download progress"}</button>
+            { export }
+            <label>
+                {"Import a progress code or file"}
+                <textarea ref={import_node_ref} />
+            </label>
+            <input type="file" accept="application/json" onchange={onfile} />
+            <button onclick={onimport}>{"Import"}</button>
+            { report }
+        </fieldset>
+    }
+}
@@ -0,0 +1,163 @@
+use std::borrow::Cow;
+
+use yew::prelude::*;
+
+use crate::components::progress_transfer::ProgressTransfer;
+use crate::hooks::continuity_switcher::use_active_continuity;
+use crate::states::active_release::{use_active_release_switcher, use_active_release_tracker};
+use crate::states::manifest::use_manifest;
+use crate::try_html;
+use crate::utils::offline::make_available_offline;
+
+/// Renders the reader settings of StoryWiki
+#[function_component]
+pub fn Settings() -> Html {
+    log::trace!("Rendering Settings");
+
+    let manifest = use_manifest();
+    let active_release = use_active_release_tracker();
+    let active_release_switcher = use_active_release_switcher();
+    let active_continuity = use_active_continuity();
+    let offline_status = use_state(|| None::<&'static str>);
+
+    let manifest = try_html!(manifest.opt());
+
+    let share_link = manifest.has_multiple_releases().then(|| {
+        let at = manifest
+            .continuities()
+            .iter()
+            .filter_map(|continuity| active_release.own(continuity.reference_name()))
+            .collect::<Vec<_>>()
+            .join(",");
+        let path = web_sys::window()
+            .and_then(|window| window.location().pathname().ok())
+            .unwrap_or_default();
+        html! {
+            <a class="share-link" href={format!("{path}?at={at}")}>
+                {"Link to this page at your progress"}
+            </a>
+        }
+    });
+
+    let sync_progress = manifest.has_release_equivalents().then(|| {
+        let checked = active_release.sync_progress();
+        let active_release_switcher = active_release_switcher.clone();
+        let onchange =
+            Callback::from(move |_: Event| active_release_switcher.set_sync_progress(!checked));
+        html! {
+            <label>
+                <input type="checkbox" checked={checked} {onchange} />
+                {"Sync progress between continuities"}
+            </label>
+        }
+    });
+
+    let flags = manifest.flags().iter().map(|flag| {
+        let checked = active_release.flag_enabled(flag);
+        let onchange = {
+            let active_release_switcher = active_release_switcher.clone();
+            let flag_reference = flag.reference_name().to_string();
+            Callback::from(move |_: Event| {
+                active_release_switcher.set_flag(flag_reference.clone(), !checked)
+            })
+        };
+        html! {
+            <label>
+                <input type="checkbox" checked={checked} {onchange} />
+                { flag.display_name() }
+                if let Some(description) = flag.description() {
+                    <small class="setting-description">{ description }</small>
+                }
+            </label>
+        }
+    });
+
+    let content_warnings = manifest.content_warning_kinds().into_iter().map(|kind| {
+        let checked = active_release.content_warning_opted_in(kind);
+        let onchange = {
+            let active_release_switcher = active_release_switcher.clone();
+            let kind = kind.to_string();
+            Callback::from(move |_: Event| {
+                active_release_switcher.set_content_warning_opted_in(kind.clone(), !checked)
+            })
+        };
+        html! {
+            <label>
+                <input type="checkbox" checked={checked} {onchange} />
+                { format!("Show {} without warning", manifest.content_warning_display_name(kind)) }
+            </label>
+        }
+    });
+
+    let remove_profile = (active_release.profiles().count() > 1).then(|| {
+        let profile = active_release.active_profile().to_string();
+        let active_release_switcher = active_release_switcher.clone();
+        let onclick = Callback::from(move |_: MouseEvent| {
+            let confirmed = web_sys::window()
+                .and_then(|window| {
+                    window
+                        .confirm_with_message(&format!("Remove the profile {profile}?"))
+                        .ok()
+                })
+                .unwrap_or_default();
+            if confirmed {
+                active_release_switcher.remove_profile(profile.clone())
+            }
+        });
+        html! {
+            <button {onclick}>
+                { format!("Remove profile {}", active_release.active_profile()) }
+            </button>
+        }
+    });
+
+    let make_offline = active_continuity.active().map(|continuity| {
+        let paths = manifest
+            .pages(continuity.reference_name())
+            .iter()
+            .flat_map(|page| page.resource_paths())
+            .map(Cow::into_owned)
+            .collect::<Vec<_>>();
+        let onclick = {
+            let offline_status = offline_status.clone();
+            Callback::from(move |_: MouseEvent| {
+                offline_status.set(Some(
+                    if make_available_offline(paths.iter().map(String::as_str)) {
+                        "Downloading pages for offline reading."
+                    } else {
+                        "Offline reading is unavailable, try reloading the page."
+                    },
+                ))
+            })
+        };
+        html! {
+            <div class="offline">
+                <button {onclick}>
+                    { format!("Make {} available offline", continuity.display_name()) }
+                </button>
+                if let Some(status) = *offline_status {
+                    <small class="setting-description">{ status }</small>
+                }
+            </div>
+        }
+    });
+
+    let settings = share_link
+        .into_iter()
+        .chain(sync_progress)
+        .chain(flags)
+        .chain(content_warnings)
+        .chain(remove_profile)
+        .chain(make_offline)
+        .collect::<Vec<_>>();
+
+    html! {
+        <details class="settings">
+            <summary>{"Settings"}</summary>
+            <div class="settings-panel">
+                { for settings }
+                <ProgressTransfer />
+            </div>
+        </details>
+    }
+}
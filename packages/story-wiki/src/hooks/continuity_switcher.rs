@@ -13,17 +13,32 @@ pub struct ContinuitySwithcerHandle {
     navigator: Navigator,
     route: Route,
     location: Location,
+    manifest: Irc<Manifest>,
 }
 
 impl ContinuitySwithcerHandle {
     pub fn switch(&self, new_continuity: &Continuity) -> HistoryResult<()> {
         match self.route.clone().with_continuity(new_continuity) {
+            Route::Page {
+                continuity_url_prefix,
+                page_reference,
+            } => {
+                let page_reference = self
+                    .equivalent_page_reference(new_continuity, &page_reference)
+                    .unwrap_or(page_reference);
+                self.navigator.push(Route::Page {
+                    continuity_url_prefix,
+                    page_reference,
+                });
+                Ok(())
+            }
             new_route @ (Route::Root
             | Route::NotFound
-            | Route::Page { .. }
             | Route::DefaultContinuityPage { .. } 
             | Route::Categories { .. } 
-            | Route::DefaultContinuityCategories) => {
+            | Route::DefaultContinuityCategories
+            | Route::Category { .. }
+            | Route::DefaultContinuityCategory { .. }) => {
                 self.navigator.push(new_route);
                 Ok(())
             }
@@ -32,18 +47,45 @@ impl ContinuitySwithcerHandle {
                 .push_with_query(new_route, self.location.query::<SearchQuery>()?),
         }
     }
+
+    /// Finds the page in the new continuity that is the same as the page in the
+    /// current continuity, falling back to the new continuities default page.
+    fn equivalent_page_reference(
+        &self,
+        new_continuity: &Continuity,
+        page_reference: &str,
+    ) -> Option<String> {
+        let current_continuity = self
+            .route
+            .continuity_url_prefix()
+            .and_then(|continuity_url_prefix| {
+                self.manifest
+                    .continuity_from_url_prefix(continuity_url_prefix)
+            })
+            .or_else(|| self.manifest.default_continuity())?;
+        self.manifest
+            .equivalent_page(
+                current_continuity.reference_name(),
+                page_reference,
+                new_continuity.reference_name(),
+            )
+            .map(|page| page.page_url().to_string())
+    }
 }
 
 #[hook]
 pub fn use_continuity_switcher() -> Option<ContinuitySwithcerHandle> {
+    let manifest = use_manifest();
     let navigator = use_navigator()?;
     let route = use_route()?;
     let location = use_location()?;
+    let manifest = manifest.opt()?;
 
     Some(ContinuitySwithcerHandle {
         navigator,
         route,
         location,
+        manifest,
     })
 }
 
@@ -0,0 +1,81 @@
+use std::cell::Cell;
+use std::rc::Rc;
+
+use gloo_timers::callback::Interval;
+use wasm_bindgen_futures::spawn_local;
+use yew::prelude::*;
+
+use crate::states::manifest::replace_manifest;
+use crate::states::pages::replace_page;
+use crate::utils::fetch::{fetch_manifest_uncached, fetch_text_uncached, versioned_path};
+
+/// How often to check for changes while authoring
+const HOT_RELOAD_INTERVAL_MILLIS: u32 = 1_000;
+
+/// Runs the check every `HOT_RELOAD_INTERVAL_MILLIS` in debug builds, skipping
+/// a tick if the previous check is still running. Checking stops when the
+/// returned interval is dropped.
+fn poll<F, Fut>(check: F) -> Option<Interval>
+where
+    F: Fn() -> Fut + 'static,
+    Fut: std::future::Future<Output = ()> + 'static,
+{
+    if !cfg!(debug_assertions) {
+        return None;
+    }
+    let checking = Rc::new(Cell::new(false));
+    Some(Interval::new(HOT_RELOAD_INTERVAL_MILLIS, move || {
+        if checking.replace(true) {
+            return;
+        }
+        let checking = checking.clone();
+        let check = check();
+        spawn_local(async move {
+            check.await;
+            checking.set(false);
+        });
+    }))
+}
+
+/// Reloads the manifest in place when it changes, in debug builds, so authors
+/// see their changes without reloading the site.
+#[hook]
+pub fn use_manifest_hot_reload() {
+    use_effect_with_deps(
+        |_| {
+            let interval = poll(|| async {
+                match fetch_manifest_uncached().await {
+                    Ok(manifest) => replace_manifest(manifest),
+                    Err(e) => log::debug!("Unable to check the manifest for changes: {e}"),
+                }
+            });
+            move || drop(interval)
+        },
+        (),
+    );
+}
+
+/// Reloads the page being read in place when it changes, in debug builds, so
+/// authors see their changes without reloading the site or losing their place.
+#[hook]
+pub fn use_page_hot_reload(resource_path: &str, content_hash: Option<&str>) {
+    let resource_path = versioned_path(resource_path, content_hash).into_owned();
+    use_effect_with_deps(
+        |resource_path| {
+            let resource_path = resource_path.clone();
+            let interval = poll(move || {
+                let resource_path = resource_path.clone();
+                async move {
+                    match fetch_text_uncached(&resource_path).await {
+                        Ok(content) => replace_page(resource_path, content),
+                        Err(e) => {
+                            log::debug!("Unable to check page {resource_path} for changes: {e}")
+                        }
+                    }
+                }
+            });
+            move || drop(interval)
+        },
+        resource_path,
+    );
+}
@@ -1 +1,3 @@
 pub mod continuity_switcher;
+pub mod hot_reload;
+pub mod prefetch;
@@ -0,0 +1,183 @@
+use std::collections::HashSet;
+
+use gloo_events::EventListener;
+use gloo_timers::callback::Timeout;
+use js_sys::Reflect;
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::JsCast;
+use web_sys::{window, Element, Url};
+use yew::prelude::*;
+use yew_router::prelude::*;
+
+use crate::routes::Route;
+use crate::states::active_release::use_active_release_tracker;
+use crate::states::manifest::{use_manifest, Manifest};
+use crate::states::pages::prefetch_pages;
+use crate::utils::cond::Observed;
+use crate::utils::fetch::versioned_path;
+
+/// Links inside the page being read
+const PAGE_LINKS: &str = "main a[href]";
+
+/// How long to wait before prefetching in browsers that cannot say when they
+/// are idle
+const IDLE_FALLBACK_DELAY_MILLIS: u32 = 2_000;
+
+/// A callback for when the browser is idle, cancelled if it is dropped first
+enum IdleCallback {
+    Idle(u32, Closure<dyn FnMut()>),
+    Timeout(Timeout),
+}
+impl IdleCallback {
+    fn new(callback: impl FnOnce() + 'static) -> Option<Self> {
+        let window = window()?;
+        if !Reflect::has(&window, &"requestIdleCallback".into()).unwrap_or(false) {
+            return Some(IdleCallback::Timeout(Timeout::new(
+                IDLE_FALLBACK_DELAY_MILLIS,
+                callback,
+            )));
+        }
+        let closure = Closure::once(callback);
+        let handle = window
+            .request_idle_callback(closure.as_ref().unchecked_ref())
+            .ok()?;
+        Some(IdleCallback::Idle(handle, closure))
+    }
+}
+impl Drop for IdleCallback {
+    fn drop(&mut self) {
+        if let (IdleCallback::Idle(handle, _), Some(window)) = (&*self, window()) {
+            window.cancel_idle_callback(*handle);
+        }
+    }
+}
+
+/// Finds the versioned resource path of the page a link leads to, if it is a
+/// page that is visible on the active release and not in a bundle.
+fn linked_resource_path(
+    manifest: &Manifest,
+    observed_releases_references: &Observed,
+    href: &str,
+) -> Option<String> {
+    let location = window()?.location();
+    let url = Url::new_with_base(href, &location.href().ok()?).ok()?;
+    if url.origin() != location.origin().ok()? {
+        return None;
+    }
+    let route = Route::recognize(&url.pathname())?;
+    let page_reference = match &route {
+        Route::Page { page_reference, .. } | Route::DefaultContinuityPage { page_reference } => {
+            page_reference
+        }
+        _ => return None,
+    };
+    let continuity = route
+        .continuity_url_prefix()
+        .and_then(|continuity_url_prefix| {
+            manifest.continuity_from_url_prefix(continuity_url_prefix)
+        })
+        .or_else(|| manifest.default_continuity())?;
+    // Pages of bundled continuities come from the bundle when they are opened,
+    // prefetching them individually would make the requests bundles avoid
+    if continuity.bundle().is_some() {
+        return None;
+    }
+    let page = manifest
+        .page(continuity.reference_name(), page_reference)
+        .or_else(|| {
+            manifest.page_redirect(
+                continuity.reference_name(),
+                page_reference,
+                observed_releases_references,
+                continuity.prefix(),
+            )
+        })?;
+    if !page.should_show(observed_releases_references, continuity.prefix()) {
+        return None;
+    }
+    let resource_path = page.resource_path(observed_releases_references, continuity.prefix());
+    let content_hash = page.content_hash(observed_releases_references, continuity.prefix());
+    Some(versioned_path(resource_path, content_hash).into_owned())
+}
+
+/// Prefetches the pages linked from the page being read once it has rendered,
+/// each one as soon as its link is hovered, and all of them when the browser is
+/// next idle.
+#[hook]
+pub fn use_link_prefetch(rendered: bool) {
+    let manifest = use_manifest();
+    let active_release_tracker = use_active_release_tracker();
+
+    let manifest = manifest.opt();
+    // Owned, as the effect outlives the borrow of the manifest
+    let observed_releases_references = manifest
+        .as_ref()
+        .map(|manifest| {
+            let observed = active_release_tracker.observed_releases_references(manifest);
+            let to_strings = |references: HashSet<&str>| {
+                references
+                    .into_iter()
+                    .map(str::to_string)
+                    .collect::<HashSet<_>>()
+            };
+            (to_strings(observed.releases), to_strings(observed.flags))
+        })
+        .unwrap_or_default();
+
+    use_effect_with_deps(
+        |(rendered, manifest, observed_releases_references)| {
+            let document = window().and_then(|window| window.document());
+            let prefetch = match (*rendered, manifest.clone(), document) {
+                (true, Some(manifest), Some(document)) => {
+                    let observed_releases_references = observed_releases_references.clone();
+                    let resolve = move |href: &str| {
+                        let (releases, flags) = &observed_releases_references;
+                        let observed_releases_references = Observed {
+                            releases: releases.iter().map(String::as_str).collect(),
+                            flags: flags.iter().map(String::as_str).collect(),
+                        };
+                        linked_resource_path(&manifest, &observed_releases_references, href)
+                    };
+                    let hover = {
+                        let resolve = resolve.clone();
+                        EventListener::new(&document, "mouseover", move |event| {
+                            let resource_path = event
+                                .target()
+                                .and_then(|target| target.dyn_into::<Element>().ok())
+                                .and_then(|target| target.closest(PAGE_LINKS).ok().flatten())
+                                .and_then(|link| link.get_attribute("href"))
+                                .and_then(|href| resolve(&href));
+                            if let Some(resource_path) = resource_path {
+                                prefetch_pages(vec![resource_path], true);
+                            }
+                        })
+                    };
+                    let idle = IdleCallback::new(move || {
+                        let links = match document.query_selector_all(PAGE_LINKS) {
+                            Ok(links) => links,
+                            Err(_) => return,
+                        };
+                        let mut resource_paths = Vec::new();
+                        for index in 0..links.length() {
+                            let resource_path = links
+                                .get(index)
+                                .and_then(|link| link.dyn_into::<Element>().ok())
+                                .and_then(|link| link.get_attribute("href"))
+                                .and_then(|href| resolve(&href));
+                            if let Some(resource_path) = resource_path {
+                                if !resource_paths.contains(&resource_path) {
+                                    resource_paths.push(resource_path);
+                                }
+                            }
+                        }
+                        prefetch_pages(resource_paths, false);
+                    });
+                    Some((hover, idle))
+                }
+                _ => None,
+            };
+            move || drop(prefetch)
+        },
+        (rendered, manifest, observed_releases_references),
+    );
+}
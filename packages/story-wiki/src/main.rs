@@ -1,4 +1,5 @@
 use story_wiki::components::main::Main;
+use story_wiki::utils::offline::register_service_worker;
 
 #[cfg(debug_assertions)]
 fn init_logging() {
@@ -12,5 +13,6 @@ fn init_logging() {
 
 fn main() {
     init_logging();
+    register_service_worker();
     yew::Renderer::<Main>::new().render();
 }
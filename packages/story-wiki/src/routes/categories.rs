@@ -1,10 +1,173 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use yew::prelude::*;
 use yew_router::prelude::*;
 
-use crate::{states::{manifest::{use_manifest, Page}, title::use_title_switcher, active_release::use_active_release_tracker}, hooks::continuity_switcher::use_active_continuity, routes::Route};
+use crate::components::md_render::MdRender;
+use crate::hooks::continuity_switcher::use_active_continuity;
+use crate::routes::Route;
+use crate::states::active_release::use_active_release_tracker;
+use crate::states::manifest::{use_manifest, Category, Continuity, Manifest, Page};
+use crate::states::title::use_title_switcher;
+use crate::utils::cond::Observed;
 
+/// A category as it should show in the category browser for the active release
+pub struct VisibleCategory<'a> {
+    /// The name pages use to refer to the category
+    pub name: String,
+    /// The path to use in the URL for the category
+    pub url: String,
+    pub display_name: String,
+    pub description: Option<&'a str>,
+    sort_order: i64,
+    /// The name of the parent category, if it is also visible
+    pub parent: Option<String>,
+    /// The visible pages directly in the category
+    pub pages: Vec<&'a Page>,
+}
+
+/// Collects every category that should show in the category browser, ordered
+/// by sort order and then display name.
+///
+/// Categories show if they contain a visible page, or are the ancestor of a
+/// category which does, and are not hidden.
+pub fn visible_categories<'a>(
+    manifest: &'a Manifest,
+    continuity: &Continuity,
+    observed_releases_references: &Observed,
+) -> Vec<VisibleCategory<'a>> {
+    let mut categories = HashMap::<String, Vec<&Page>>::new();
+    for page in manifest.pages(continuity.reference_name()) {
+        if page.should_show(observed_releases_references, continuity.prefix()) {
+            for category in page.categories(
+                observed_releases_references,
+                continuity.prefix(),
+                manifest.unknown_category(),
+            ) {
+                categories.entry(category).or_default().push(page);
+            }
+        }
+    }
+
+    let parent_of = |name: &str| {
+        manifest
+            .category(name)
+            .and_then(|category| category.parent(observed_releases_references, continuity.prefix()))
+            .map(str::to_string)
+    };
+
+    let mut ancestors = Vec::new();
+    for name in categories.keys() {
+        let mut seen = HashSet::new();
+        let mut next = parent_of(name);
+        while let Some(parent) = next {
+            if !seen.insert(parent.clone()) {
+                break;
+            }
+            next = parent_of(&parent);
+            ancestors.push(parent);
+        }
+    }
+    for ancestor in ancestors {
+        categories.entry(ancestor).or_default();
+    }
+
+    categories.retain(|name, _| !manifest.category(name).map_or(false, Category::hidden));
+
+    let names = categories.keys().cloned().collect::<HashSet<_>>();
+    let mut visible = categories
+        .into_iter()
+        .map(|(name, mut pages)| {
+            let config = manifest.category(&name);
+            pages.sort_by_key(|page| {
+                page.display_name(observed_releases_references, continuity.prefix())
+                    .to_string()
+            });
+            VisibleCategory {
+                url: manifest.category_url(&name).to_string(),
+                display_name: config
+                    .map(|config| {
+                        config
+                            .display_name(observed_releases_references, continuity.prefix())
+                            .to_string()
+                    })
+                    .unwrap_or_else(|| name.clone()),
+                description: config.and_then(Category::description),
+                sort_order: config.map_or(0, Category::sort_order),
+                parent: parent_of(&name).filter(|parent| parent != &name && names.contains(parent)),
+                pages,
+                name,
+            }
+        })
+        .collect::<Vec<_>>();
+    visible.sort_by(|a, b| (a.sort_order, &a.display_name).cmp(&(b.sort_order, &b.display_name)));
+    visible
+}
+
+/// Renders the links to the pages of a category
+pub fn category_pages_list(
+    pages: &[&Page],
+    continuity: &Continuity,
+    observed_releases_references: &Observed,
+) -> Html {
+    let pages_list = pages.iter().map(|page| {
+        html! {
+            <li class="category-entry">
+                <Link<Route> to={
+                    Route::Page { continuity_url_prefix: continuity.url_prefix().to_string(), page_reference: page.page_url().to_string() }
+                }>
+                    { page.display_name(observed_releases_references, continuity.prefix()) }
+                </Link<Route>>
+            </li>
+        }
+    });
+    html! {
+        <ul class="noindent">
+            { for pages_list }
+        </ul>
+    }
+}
+
+/// Renders the links to the subcategories of a category
+pub fn subcategories_list(
+    categories: &[VisibleCategory],
+    parent: &str,
+    continuity: &Continuity,
+) -> Html {
+    let subcategories_list = categories
+        .iter()
+        .filter(|category| category.parent.as_deref() == Some(parent))
+        .map(|category| {
+            html! {
+                <li class="category-entry subcategory-entry">
+                    <Link<Route> to={
+                        Route::Category { continuity_url_prefix: continuity.url_prefix().to_string(), category_url: category.url.clone() }
+                    }>
+                        { &category.display_name }
+                    </Link<Route>>
+                </li>
+            }
+        });
+    html! {
+        <ul class="noindent">
+            { for subcategories_list }
+        </ul>
+    }
+}
+
+/// Renders the description of a category
+pub fn category_description(description: Option<&str>, continuity: &Continuity) -> Html {
+    description
+        .map(|description| {
+            html! {
+                <MdRender
+                    content={description.to_string()}
+                    continuity={continuity.reference_name().to_string()}
+                />
+            }
+        })
+        .unwrap_or_default()
+}
 
 #[function_component]
 pub fn RouteCategories() -> Html {
@@ -36,40 +199,27 @@ pub fn RouteCategories() -> Html {
     let observed_releases_references =
         active_release_tracker.observed_releases_references(&manifest);
 
+    let categories = visible_categories(&manifest, continuity, &observed_releases_references);
 
-    let mut categories = HashMap::<String, Vec<&Page>>::new();
-    for page in manifest.pages(continuity.reference_name()) {
-        if page.should_show(&observed_releases_references, continuity.prefix()) {
-            for category in page.categories(&observed_releases_references, continuity.prefix()) {
-                categories.entry(category).or_default().push(page);
-            }
-        }
-    }
-    let mut ordered_categories = categories.into_iter().collect::<Vec<_>>();
-    ordered_categories.sort_by_key(|(category, _)| category.clone());
-    
-    let out = ordered_categories.into_iter().map(|(category, mut pages)| {
-        pages.sort_by_key(|page| page.display_name().to_string());
-        let pages_list = pages.into_iter().map(|page| {
+    let out = categories
+        .iter()
+        .filter(|category| category.parent.is_none())
+        .map(|category| {
             html! {
-                <li class="category-entry">
-                    <Link<Route> to={
-                        Route::Page { continuity_url_prefix: continuity.url_prefix().to_string(), page_reference: page.page_url().to_string() }
-                    }>
-                        { page.display_name() }
-                    </Link<Route>>
-                </li>
+                <>
+                    <h2>
+                        <Link<Route> to={
+                            Route::Category { continuity_url_prefix: continuity.url_prefix().to_string(), category_url: category.url.clone() }
+                        }>
+                            { &category.display_name }
+                        </Link<Route>>
+                    </h2>
+                    { category_description(category.description, continuity) }
+                    { subcategories_list(&categories, &category.name, continuity) }
+                    { category_pages_list(&category.pages, continuity, &observed_releases_references) }
+                </>
             }
         });
-        html! {
-            <>
-                <h2>{category}</h2>
-                <ul class="noindent">
-                { for pages_list }
-                </ul>
-            </>
-        }
-    });
 
     html! {
         <main>
@@ -78,4 +228,4 @@ pub fn RouteCategories() -> Html {
             </article>
         </main>
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,115 @@
+use std::collections::HashSet;
+
+use yew::prelude::*;
+use yew_router::prelude::*;
+
+use crate::hooks::continuity_switcher::use_active_continuity;
+use crate::routes::categories::{
+    category_description, category_pages_list, subcategories_list, visible_categories,
+};
+use crate::routes::Route;
+use crate::states::active_release::use_active_release_tracker;
+use crate::states::manifest::use_manifest;
+use crate::states::title::use_title_switcher;
+
+#[derive(PartialEq, Properties)]
+pub struct RouteCategoryProps {
+    pub category_url: String,
+}
+
+#[function_component]
+pub fn RouteCategory(props: &RouteCategoryProps) -> Html {
+    log::trace!("Rendering RouteCategory `{}`", props.category_url);
+
+    let manifest = use_manifest();
+    let title_switcher = use_title_switcher();
+    let active_release_tracker = use_active_release_tracker();
+    let active_continuity = use_active_continuity();
+    let navigator = use_navigator();
+
+    let continuity = active_continuity.active().unwrap();
+    let manifest = manifest.unwrap();
+    let navigator = navigator.unwrap();
+
+    let manifest_has_multiple_continuities = manifest.has_multiple_continuities();
+    let continuity_in_url = active_continuity.is_from_route();
+    if !manifest_has_multiple_continuities && continuity_in_url {
+        navigator.replace(Route::DefaultContinuityCategory {
+            category_url: props.category_url.clone(),
+        });
+    }
+    if manifest_has_multiple_continuities && !continuity_in_url {
+        navigator.replace(Route::Category {
+            continuity_url_prefix: continuity.url_prefix().to_string(),
+            category_url: props.category_url.clone(),
+        })
+    }
+
+    let observed_releases_references =
+        active_release_tracker.observed_releases_references(&manifest);
+
+    let categories = visible_categories(&manifest, continuity, &observed_releases_references);
+
+    let category = match categories
+        .iter()
+        .find(|category| category.url == props.category_url)
+    {
+        Some(category) => category,
+        None => return html! { <main>{"Category not found."}</main> },
+    };
+
+    title_switcher.page(category.display_name.clone());
+
+    let mut ancestors = Vec::new();
+    let mut seen = HashSet::new();
+    let mut next = category.parent.as_deref();
+    while let Some(parent) = next.filter(|parent| seen.insert(*parent)) {
+        let parent = match categories.iter().find(|category| category.name == parent) {
+            Some(parent) => parent,
+            None => break,
+        };
+        ancestors.push(parent);
+        next = parent.parent.as_deref();
+    }
+    let breadcrumbs = ancestors.into_iter().rev().map(|ancestor| {
+        html! {
+            <>
+                {" › "}
+                <Link<Route> to={
+                    Route::Category { continuity_url_prefix: continuity.url_prefix().to_string(), category_url: ancestor.url.clone() }
+                }>
+                    { &ancestor.display_name }
+                </Link<Route>>
+            </>
+        }
+    });
+
+    let has_subcategories = categories
+        .iter()
+        .any(|subcategory| subcategory.parent.as_deref() == Some(category.name.as_str()));
+
+    html! {
+        <main>
+            <article>
+                <nav class="breadcrumbs">
+                    <Link<Route> to={
+                        Route::Categories { continuity_url_prefix: continuity.url_prefix().to_string() }
+                    }>
+                        {"Categories"}
+                    </Link<Route>>
+                    { for breadcrumbs }
+                </nav>
+                <h1>{ &category.display_name }</h1>
+                { category_description(category.description, continuity) }
+                if has_subcategories {
+                    <h2>{"Subcategories"}</h2>
+                    { subcategories_list(&categories, &category.name, continuity) }
+                }
+                if !category.pages.is_empty() {
+                    <h2>{"Pages"}</h2>
+                    { category_pages_list(&category.pages, continuity, &observed_releases_references) }
+                }
+            </article>
+        </main>
+    }
+}
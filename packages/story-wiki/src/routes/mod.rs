@@ -6,12 +6,14 @@ pub mod page;
 pub mod root;
 pub mod search;
 pub mod categories;
+pub mod category;
 
 use not_found::RouteNotFound;
 use page::RoutePage;
 use root::RouteRoot;
 use search::RouteSearch;
 use categories::RouteCategories;
+use category::RouteCategory;
 
 use crate::states::manifest::Continuity;
 
@@ -42,6 +44,15 @@ pub enum Route {
     #[at("/:continuity_url_prefix/categories")]
     Categories { continuity_url_prefix: String },
 
+    #[at("/categories/:category_url")]
+    DefaultContinuityCategory { category_url: String },
+
+    #[at("/:continuity_url_prefix/categories/:category_url")]
+    Category {
+        continuity_url_prefix: String,
+        category_url: String,
+    },
+
     #[not_found]
     #[at("/404")]
     NotFound,
@@ -56,7 +67,11 @@ impl Route {
             | Route::Search {
                 continuity_url_prefix,
             }
-            | Route::Categories { continuity_url_prefix } => Some(continuity_url_prefix.as_str()),
+            | Route::Categories { continuity_url_prefix }
+            | Route::Category {
+                continuity_url_prefix,
+                ..
+            } => Some(continuity_url_prefix.as_str()),
             _ => None,
         }
     }
@@ -68,7 +83,11 @@ impl Route {
         }
         | Route::Search {
             continuity_url_prefix,
-        } | Route::Categories { continuity_url_prefix } = &mut self
+        } | Route::Categories { continuity_url_prefix }
+        | Route::Category {
+            continuity_url_prefix,
+            ..
+        } = &mut self
         {
             *continuity_url_prefix = new_continuity.url_prefix().to_string();
         } else if let Route::DefaultContinuityPage { page_reference } = self {
@@ -76,6 +95,11 @@ impl Route {
                 page_reference,
                 continuity_url_prefix: new_continuity.url_prefix().to_string(),
             }
+        } else if let Route::DefaultContinuityCategory { category_url } = self {
+            self = Route::Category {
+                category_url,
+                continuity_url_prefix: new_continuity.url_prefix().to_string(),
+            }
         }
         self
     }
@@ -90,6 +114,10 @@ pub fn switch(routes: &Route) -> Html {
         }
         Route::DefaultContinuitySearch | Route::Search { .. } => html! { <RouteSearch /> },
         Route::DefaultContinuityCategories | Route::Categories { .. } => html! { <RouteCategories /> },
+        Route::DefaultContinuityCategory { category_url }
+        | Route::Category { category_url, .. } => {
+            html! { <RouteCategory {category_url} /> }
+        }
         Route::NotFound => html! { <RouteNotFound />},
     }
 }
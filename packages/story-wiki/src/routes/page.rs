@@ -1,11 +1,11 @@
 use yew::prelude::*;
-use yew_router::hooks::use_navigator;
+use yew_router::hooks::{use_location, use_navigator};
 
 use crate::components::page_render::PageRender;
 use crate::hooks::continuity_switcher::use_active_continuity;
 use crate::routes::search::SearchQuery;
 use crate::routes::Route;
-use crate::states::active_release::use_active_release_tracker;
+use crate::states::active_release::{use_active_release_tracker, PreviewQuery};
 use crate::states::manifest::use_manifest;
 use crate::states::title::use_title_switcher;
 
@@ -23,6 +23,8 @@ pub fn RoutePage(props: &RoutePageProps) -> Html {
     let active_release_tracker = use_active_release_tracker();
     let active_continuity = use_active_continuity();
     let navigator = use_navigator();
+    let location = use_location();
+    let revealed = use_state(|| None::<String>);
 
     let continuity = active_continuity.active().unwrap();
     let manifest = manifest.unwrap();
@@ -48,9 +50,43 @@ pub fn RoutePage(props: &RoutePageProps) -> Html {
         active_release_tracker.observed_releases_references(&manifest);
 
     match page {
-        None => html! { <main>{"Page not found."}</main> },
+        None => {
+            if let Some(page) = manifest.page_redirect(
+                continuity.reference_name(),
+                &props.page_reference,
+                &observed_releases_references,
+                continuity.prefix(),
+            ) {
+                log::trace!(
+                    "Redirecting `{}` to `{}`",
+                    props.page_reference,
+                    page.page_url()
+                );
+                let route = Route::Page {
+                    continuity_url_prefix: continuity.url_prefix().to_string(),
+                    page_reference: page.page_url().to_string(),
+                };
+                // Keep previewing a shared link through the redirect
+                match location.and_then(|location| location.query::<PreviewQuery>().ok()) {
+                    Some(preview_query) => {
+                        navigator.replace_with_query(route, preview_query).unwrap()
+                    }
+                    None => navigator.replace(route),
+                }
+                return html! {};
+            }
+            html! { <main>{"Page not found."}</main> }
+        }
         Some(page) => {
             if !page.should_show(&observed_releases_references, &continuity.prefix()) {
+                if active_release_tracker.is_previewing() {
+                    return html! {
+                        <main>
+                            {"This page was shared from further along than your progress, "}
+                            {"adopt the shared progress to view it."}
+                        </main>
+                    };
+                }
                 log::trace!("Page not available on active release");
                 navigator
                     .replace_with_query(
@@ -64,11 +100,42 @@ pub fn RoutePage(props: &RoutePageProps) -> Html {
                     .unwrap();
                 return html! {}
             }
-            title_switcher.page(page.display_name().to_string());
+            title_switcher.page(
+                page.display_name(&observed_releases_references, continuity.prefix())
+                    .to_string(),
+            );
+            let warnings = page
+                .content_warnings()
+                .iter()
+                .filter(|kind| !active_release_tracker.content_warning_opted_in(kind))
+                .map(|kind| manifest.content_warning_display_name(kind))
+                .collect::<Vec<_>>();
+            if !warnings.is_empty() && revealed.as_deref() != Some(page.page_url()) {
+                let onclick = {
+                    let page_url = page.page_url().to_string();
+                    Callback::from(move |_: MouseEvent| revealed.set(Some(page_url.clone())))
+                };
+                return html! {
+                    <main>
+                        <aside class="content-warning-interstitial">
+                            <p>{ format!("Content warning: {}", warnings.join(", ")) }</p>
+                            <button {onclick}>{"Show page"}</button>
+                        </aside>
+                    </main>
+                };
+            }
             html! {
                 <main>
                     <PageRender
-                        resource_path={page.resource_path().to_string()}
+                        resource_path={
+                            page.resource_path(&observed_releases_references, continuity.prefix())
+                                .to_string()
+                        }
+                        content_hash={
+                            page.content_hash(&observed_releases_references, continuity.prefix())
+                                .map(str::to_string)
+                        }
+                        bundle={continuity.bundle().map(str::to_string)}
                         continuity={continuity.reference_name().to_string()}
                     />
                 </main>
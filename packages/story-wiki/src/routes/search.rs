@@ -94,7 +94,8 @@ pub fn RouteSearch() -> Html {
         .iter()
         .filter(|page| page.should_show(&observed_releases, &continuity.prefix()))
     {
-        let tokenized_page_title = tokenize(&cleanup(&page.display_name())).collect::<Vec<_>>();
+        let display_name = page.display_name(&observed_releases, &continuity.prefix());
+        let tokenized_page_title = tokenize(&cleanup(&display_name)).collect::<Vec<_>>();
         let tokenized_refernece_name = tokenize(&cleanup(&page.page_url())).collect::<Vec<_>>();
         if tokenized_query == tokenized_page_title || tokenized_query == tokenized_refernece_name {
             navigator.replace(Route::Page {
@@ -111,7 +112,7 @@ pub fn RouteSearch() -> Html {
             let entry = title_results
                 .entry(SearchResult {
                     reference_name: page.page_url().to_string(),
-                    title: page.display_name().to_string(),
+                    title: display_name.to_string(),
                 })
                 .or_default();
             *entry = (*entry).max(page_title_score);
@@ -132,14 +133,18 @@ pub fn RouteSearch() -> Html {
                 let entry = title_results
                     .entry(SearchResult {
                         reference_name: page.page_url().to_string(),
-                        title: page.display_name().to_string(),
+                        title: display_name.to_string(),
                     })
                     .or_default();
                 *entry = (*entry).max(page_title_peer_score);
             }
         }
 
-        for category in page.categories(&observed_releases, &continuity.prefix()) {
+        for category in page.categories(
+            &observed_releases,
+            &continuity.prefix(),
+            manifest.unknown_category(),
+        ) {
             let tokenized_category = tokenize(&cleanup(&category)).collect::<Vec<_>>();
             let page_category_score = tokenized_query
                 .iter()
@@ -149,7 +154,7 @@ pub fn RouteSearch() -> Html {
                 let entry = category_results
                     .entry(SearchResult {
                         reference_name: page.page_url().to_string(),
-                        title: page.display_name().to_string(),
+                        title: display_name.to_string(),
                     })
                     .or_default();
                 *entry = (*entry).max(page_category_score);
@@ -165,7 +170,7 @@ pub fn RouteSearch() -> Html {
             let entry = keyword_results
                 .entry(SearchResult {
                     reference_name: page.page_url().to_string(),
-                    title: page.display_name().to_string(),
+                    title: display_name.to_string(),
                 })
                 .or_default();
             *entry = (*entry).max(page_keyword_score);
@@ -1,23 +1,420 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::rc::Rc;
 use std::sync::Once;
 
 use gloo_events::EventListener;
+use gloo_timers::callback::Timeout;
 use serde::{Deserialize, Serialize};
 use yew::prelude::*;
+use yew_router::hooks::use_location;
 use yewdux::prelude::*;
-use yewdux::storage::{load, save, Area};
 
-use crate::states::manifest::{use_manifest, Manifest};
+use crate::states::manifest::{use_manifest, Flag, Manifest, Release};
+use crate::utils::cond::Observed;
 use crate::utils::downloadable_resource::DownloadableResource;
 use crate::utils::irc::Irc;
 
 static STATE_LISTENER_ON: Once = Once::new();
 
-#[derive(PartialEq, Clone, Default, Serialize, Deserialize)]
+/// How long peeking ahead lasts before reverting to the saved progress
+const PEEK_DURATION_MILLIS: u32 = 10 * 60 * 1000;
+
+/// The version of the format `State` is stored in, when changing the format
+/// increment this and add a migration from the previous version to `migrate`
+const STATE_VERSION: u32 = 1;
+
+/// The name of the profile readers start with, and that progress stored before
+/// profiles existed is migrated into
+const DEFAULT_PROFILE: &str = "Default";
+
+#[derive(PartialEq, Clone, Serialize, Deserialize)]
+#[serde(from = "StoredState")]
 struct State {
+    /// The name of the profile in use
+    active_profile: String,
+    /// Map from profile name to the progress and settings of that reader
+    profiles: BTreeMap<String, Profile>,
+}
+
+impl Default for State {
+    fn default() -> Self {
+        State::from(StoredState::Legacy(Profile::default()))
+    }
+}
+
+impl State {
+    fn profile(&self) -> &Profile {
+        &self.profiles[&self.active_profile]
+    }
+
+    fn profile_mut(&mut self) -> &mut Profile {
+        self.profiles
+            .entry(self.active_profile.clone())
+            .or_default()
+    }
+}
+
+/// Every format the state has been stored in
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StoredState {
+    Profiles {
+        active_profile: String,
+        profiles: BTreeMap<String, Profile>,
+    },
+    /// The progress of a single reader, from before profiles
+    Legacy(Profile),
+}
+
+impl From<StoredState> for State {
+    fn from(stored_state: StoredState) -> Self {
+        let (active_profile, mut profiles) = match stored_state {
+            StoredState::Profiles {
+                active_profile,
+                profiles,
+            } => (active_profile, profiles),
+            StoredState::Legacy(profile) => (
+                DEFAULT_PROFILE.to_string(),
+                BTreeMap::from([(DEFAULT_PROFILE.to_string(), profile)]),
+            ),
+        };
+        profiles.entry(active_profile.clone()).or_default();
+        State {
+            active_profile,
+            profiles,
+        }
+    }
+}
+
+/// The progress and settings of a single reader
+#[derive(PartialEq, Clone, Default, Serialize, Deserialize)]
+struct Profile {
+    /// Map from `Continuity.reference_name` to `Release.reference_name`
+    releases: HashMap<String, String>,
+    /// Map from `Continuity.reference_name` to the `Release.reference_name`s
+    /// of optional releases that have been observed
+    #[serde(default)]
+    optional: HashMap<String, HashSet<String>>,
+    /// If changing the release of one continuity should advance the releases of
+    /// continuities with equivalent releases
+    #[serde(default)]
+    sync_progress: bool,
+    /// Map from `Continuity.reference_name` to the `Chapter.reference_name` of
+    /// the chapter of the active release the reader is up to, if they have not
+    /// finished it
+    #[serde(default)]
+    chapters: HashMap<String, String>,
+    /// Map from `Flag.reference_name` to if the reader has enabled the flag
+    #[serde(default)]
+    flags: HashMap<String, bool>,
+    /// The kinds of content warning the reader has opted in to seeing content
+    /// for without a warning
+    #[serde(default)]
+    content_warnings: HashSet<String>,
+}
+
+impl Profile {
+    /// Removes anything that does not exist in the manifest, returning a
+    /// description of everything removed
+    fn reconcile(&mut self, manifest: &Manifest) -> Vec<String> {
+        let mut removed = Vec::new();
+        self.releases
+            .retain(|continuity_reference, release_reference| {
+                // Optional releases cannot be a position on the main line
+                let exists = manifest
+                    .release(continuity_reference, release_reference)
+                    .map_or(false, |release| !release.optional());
+                if !exists {
+                    removed.push(format!(
+                        "release `{release_reference}` of `{continuity_reference}`"
+                    ));
+                }
+                exists
+            });
+        self.chapters
+            .retain(|continuity_reference, chapter_reference| {
+                let exists = manifest
+                    .releases(continuity_reference)
+                    .iter()
+                    .any(|release| release.chapter(chapter_reference).is_some());
+                if !exists {
+                    removed.push(format!(
+                        "chapter `{chapter_reference}` of `{continuity_reference}`"
+                    ));
+                }
+                exists
+            });
+        for (continuity_reference, optional) in self.optional.iter_mut() {
+            optional.retain(|release_reference| {
+                let exists = manifest
+                    .release(continuity_reference, release_reference)
+                    .map_or(false, Release::optional);
+                if !exists {
+                    removed.push(format!(
+                        "optional release `{release_reference}` of `{continuity_reference}`"
+                    ));
+                }
+                exists
+            });
+        }
+        self.flags.retain(|flag_reference, _| {
+            let exists = manifest.flag(flag_reference).is_some();
+            if !exists {
+                removed.push(format!("flag `{flag_reference}`"));
+            }
+            exists
+        });
+        let content_warning_kinds = manifest.content_warning_kinds();
+        self.content_warnings.retain(|kind| {
+            let exists = content_warning_kinds.contains(&kind.as_str());
+            if !exists {
+                removed.push(format!("content warning `{kind}`"));
+            }
+            exists
+        });
+        removed
+    }
+
+    /// Advances every other continuity to the release equivalent to the given
+    /// one, unless it has already been observed.
+    fn sync_releases(
+        &mut self,
+        manifest: &Manifest,
+        continuity_reference: &str,
+        release_reference: &str,
+    ) {
+        for continuity in manifest.continuities() {
+            let target_continuity_reference = continuity.reference_name();
+            if target_continuity_reference == continuity_reference {
+                continue;
+            }
+            let equivalent = match manifest.equivalent_release(
+                continuity_reference,
+                release_reference,
+                target_continuity_reference,
+            ) {
+                Some(equivalent) => equivalent,
+                None => continue,
+            };
+            let already_observed = self
+                .releases
+                .get(target_continuity_reference)
+                .map(String::as_str)
+                .or_else(|| {
+                    manifest
+                        .default_release(target_continuity_reference)
+                        .map(Release::reference_name)
+                })
+                .map_or(false, |active_release| {
+                    manifest
+                        .releases_up_to(target_continuity_reference, active_release)
+                        .contains(&equivalent)
+                });
+            if !already_observed {
+                log::debug!(
+                    "Syncing {target_continuity_reference} to {}",
+                    equivalent.reference_name()
+                );
+                self.releases.insert(
+                    target_continuity_reference.to_string(),
+                    equivalent.reference_name().to_string(),
+                );
+                self.chapters.remove(target_continuity_reference);
+            }
+        }
+    }
+}
+
+/// Releases the reader is temporarily peeking ahead at, this is not persisted
+/// so it reverts when the tab is closed
+#[derive(PartialEq, Clone, Default, Store)]
+struct PeekState {
+    /// If picking a release should peek at it rather than save it as progress
+    enabled: bool,
     /// Map from `Continuity.reference_name` to `Release.reference_name`
     releases: HashMap<String, String>,
+    /// Incremented on each peek, so only the timer of the latest peek reverts
+    generation: u32,
+}
+
+/// The query of a link shared at the senders progress
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PreviewQuery {
+    /// Comma separated `Release.reference_name`s, at most one per continuity
+    pub at: String,
+}
+
+/// A release a link was shared at, and the release it is previewed at
+struct SharedRelease {
+    /// The `Release.reference_name` the link was shared at
+    shared: String,
+    /// The `Release.reference_name` of the latest release observed both at the
+    /// shared release and the readers own progress
+    previewed: String,
+}
+
+/// Works out what each continuity in the `at` query of a shared link should
+/// be previewed at, never further along than the readers own progress.
+fn shared_releases(state: &State, manifest: &Manifest, at: &str) -> HashMap<String, SharedRelease> {
+    let mut shared_releases = HashMap::new();
+    for release_reference in at.split(',').map(str::trim) {
+        // Optional releases are not a position on the main line, so cannot be
+        // previewed at
+        let continuity = manifest.continuities().iter().find(|continuity| {
+            manifest
+                .release(continuity.reference_name(), release_reference)
+                .map_or(false, |release| !release.optional())
+        });
+        let continuity_reference = match continuity {
+            Some(continuity) => continuity.reference_name(),
+            None => {
+                log::warn!("Unknown release `{release_reference}` in shared link");
+                continue;
+            }
+        };
+        let own_releases = state
+            .profile()
+            .releases
+            .get(continuity_reference)
+            .map(String::as_str)
+            .or_else(|| {
+                manifest
+                    .default_release(continuity_reference)
+                    .map(Release::reference_name)
+            })
+            .map(|own_release| manifest.releases_up_to(continuity_reference, own_release))
+            .unwrap_or_default();
+        let shared = manifest.releases_up_to(continuity_reference, release_reference);
+        let previewed = own_releases
+            .iter()
+            .zip(&shared)
+            .take_while(|(own, shared)| std::ptr::eq(**own, **shared))
+            .last();
+        if let Some((previewed, _)) = previewed {
+            shared_releases.insert(
+                continuity_reference.to_string(),
+                SharedRelease {
+                    shared: release_reference.to_string(),
+                    previewed: previewed.reference_name().to_string(),
+                },
+            );
+        }
+    }
+    shared_releases
+}
+
+/// The state as it is stored, with the version of its format
+#[derive(Serialize, Deserialize)]
+struct StoredEnvelope<T> {
+    version: u32,
+    state: T,
+}
+
+/// The local storage key the state is stored under
+const STORAGE_KEY: &str = "story-wiki-progress";
+
+/// The local storage key the state was stored under before `STORAGE_KEY`,
+/// which is where `yewdux` stored it. This is the type's name, so it can
+/// change between builds, and is only read to move the state to `STORAGE_KEY`.
+fn legacy_storage_key() -> &'static str {
+    std::any::type_name::<State>()
+}
+
+fn local_storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok().flatten()
+}
+
+/// Reads the state as it is stored, moving it from the legacy key to
+/// `STORAGE_KEY` if it has not been moved yet
+fn read_stored_state(storage: &web_sys::Storage) -> Option<String> {
+    if let Ok(Some(stored)) = storage.get_item(STORAGE_KEY) {
+        return Some(stored);
+    }
+    let stored = storage.get_item(legacy_storage_key()).ok().flatten()?;
+    if storage.set_item(STORAGE_KEY, &stored).is_ok() {
+        log::info!("Moved stored state to {STORAGE_KEY}");
+        if storage.remove_item(legacy_storage_key()).is_err() {
+            log::warn!("Unable to remove stored state from its legacy key");
+        }
+    } else {
+        log::error!("Unable to move stored state to {STORAGE_KEY}");
+    }
+    Some(stored)
+}
+
+/// Upgrades a state stored in the given version of the format to `State`
+fn migrate(version: u32, state: serde_json::Value) -> Result<State, String> {
+    match version {
+        // Before the envelope the state was stored unversioned, either as a
+        // single profile or as profiles, which `StoredState` accepts both of
+        0 | 1 => serde_json::from_value(state).map_err(|e| e.to_string()),
+        version => Err(format!("Unknown state version {version}")),
+    }
+}
+
+/// Reads the state as it is stored, in any version of the format
+fn parse_stored_state(stored: &str) -> Result<State, String> {
+    let value = serde_json::from_str::<serde_json::Value>(stored).map_err(|e| e.to_string())?;
+    match serde_json::from_value::<StoredEnvelope<serde_json::Value>>(value.clone()) {
+        Ok(envelope) => migrate(envelope.version, envelope.state),
+        Err(_) => migrate(0, value),
+    }
+}
+
+/// Loads the state from local storage, falling back to the default state if
+/// there is none or it cannot be read. State that cannot be read is backed up
+/// rather than lost.
+fn load_state() -> State {
+    let storage = match local_storage() {
+        Some(storage) => storage,
+        None => {
+            log::warn!("Local storage is not available, progress will not be saved");
+            return State::default();
+        }
+    };
+    let stored = match read_stored_state(&storage) {
+        Some(stored) => stored,
+        None => return State::default(),
+    };
+    parse_stored_state(&stored).unwrap_or_else(|e| {
+        log::error!("Unable to load state, using the default state: {e}");
+        let backup_key = format!("{STORAGE_KEY}.backup");
+        if storage.set_item(&backup_key, &stored).is_err() {
+            log::error!("Unable to back up the unreadable state");
+        }
+        State::default()
+    })
+}
+
+fn save_state(state: &State) {
+    let envelope = StoredEnvelope {
+        version: STATE_VERSION,
+        state,
+    };
+    let stored = match serde_json::to_string(&envelope) {
+        Ok(stored) => stored,
+        Err(e) => {
+            log::error!("Unable to serialize state: {e}");
+            return;
+        }
+    };
+    if let Some(storage) = local_storage() {
+        if storage.set_item(STORAGE_KEY, &stored).is_err() {
+            log::error!("Unable to save state");
+        }
+    }
+}
+
+/// Removes anything from every profile that no longer exists in the manifest,
+/// such as a release that has been removed since it was picked.
+pub(crate) fn reconcile_with_manifest(manifest: &Manifest) {
+    Dispatch::<State>::new().reduce(|state| {
+        for (name, profile) in state.profiles.iter_mut() {
+            for removed in profile.reconcile(manifest) {
+                log::warn!("Removed {removed} from profile {name} as it no longer exists");
+            }
+        }
+    })
 }
 
 impl Store for State {
@@ -25,27 +422,51 @@ impl Store for State {
         STATE_LISTENER_ON.call_once(|| {
             EventListener::new(&web_sys::window().unwrap(), "storage", move |_| {
                 log::debug!("Received storage event");
-                Dispatch::<State>::new()
-                    .reduce(|state| *state = load(Area::Local).unwrap().unwrap());
+                // Only set the state when it differs, as setting it saves it,
+                // which would send a storage event back to the other context
+                let dispatch = Dispatch::<State>::new();
+                let state = load_state();
+                if *dispatch.get() != state {
+                    dispatch.set(state);
+                }
             })
             .forget();
         });
-        load(Area::Local)
-            .expect("Unable to load state")
-            .unwrap_or_default()
+        load_state()
     }
 
     fn changed(&mut self) {
-        save(self, Area::Local).expect("Unable to save state");
+        save_state(self);
     }
 }
 
 pub struct ReleaseTrackerHandle {
     state: Rc<State>,
     manifest: DownloadableResource<Irc<Manifest>>,
+    /// Map from `Continuity.reference_name` to the release a shared link is
+    /// being previewed at
+    shared_releases: HashMap<String, SharedRelease>,
+    peek: Rc<PeekState>,
 }
 impl ReleaseTrackerHandle {
+    /// The release of the continuity that content is shown at, which is the
+    /// readers own progress unless they are peeking ahead or previewing a
+    /// shared link
     pub fn active(&self, continuity_reference: &str) -> Option<&str> {
+        self.peek
+            .releases
+            .get(continuity_reference)
+            .or_else(|| {
+                self.shared_releases
+                    .get(continuity_reference)
+                    .map(|shared_release| &shared_release.previewed)
+            })
+            .map(String::as_str)
+            .or_else(|| self.own(continuity_reference))
+    }
+
+    /// The release of the continuity the reader has stored as their progress
+    pub fn own(&self, continuity_reference: &str) -> Option<&str> {
         let get_default = || {
             self.manifest
                 .as_ref()
@@ -54,58 +475,442 @@ impl ReleaseTrackerHandle {
                 .map(|r| r.reference_name())
         };
         self.state
+            .profile()
             .releases
             .get(continuity_reference)
             .map(|r| r.as_str())
             .or_else(get_default)
     }
 
-    pub fn observed_releases_references<'a>(&self, manifest: &'a Manifest) -> HashSet<&'a str> {
-        manifest
-            .continuities()
+    /// The chapter of the active release the reader is up to, or `None` if
+    /// they have observed the whole release
+    pub fn active_chapter(&self, continuity_reference: &str) -> Option<&str> {
+        if self.active(continuity_reference) != self.own(continuity_reference) {
+            return None;
+        }
+        self.state
+            .profile()
+            .chapters
+            .get(continuity_reference)
+            .map(|c| c.as_str())
+    }
+
+    /// If picking a release will peek at it rather than save it as progress
+    pub fn peek_enabled(&self) -> bool {
+        self.peek.enabled
+    }
+
+    /// The `Continuity.reference_name`s and `Release.reference_name`s being
+    /// peeked at
+    pub fn peeked_releases(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.peek
+            .releases
             .iter()
-            .flat_map(|continuity| {
-                let active_release = self.active(&continuity.reference_name());
-                active_release.into_iter().flat_map(move |active_release| {
-                    let mut seen = false;
-                    manifest
-                        .releases(&continuity.reference_name())
-                        .iter()
-                        .take_while(move |release| {
-                            let was_seen = seen;
-                            seen = release.reference_name() == active_release;
-                            !was_seen
-                        })
-                })
+            .map(|(continuity_reference, release_reference)| {
+                (continuity_reference.as_str(), release_reference.as_str())
             })
-            .map(|release| release.reference_name())
-            .collect()
+    }
+
+    /// If a shared link is being previewed at the progress it was shared at
+    pub fn is_previewing(&self) -> bool {
+        !self.shared_releases.is_empty()
+    }
+
+    /// The `Continuity.reference_name`s and `Release.reference_name`s a
+    /// shared link being previewed was shared at
+    pub fn shared_releases(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.shared_releases
+            .iter()
+            .map(|(continuity_reference, shared_release)| {
+                (
+                    continuity_reference.as_str(),
+                    shared_release.shared.as_str(),
+                )
+            })
+    }
+
+    pub fn active_profile(&self) -> &str {
+        &self.state.active_profile
+    }
+
+    pub fn profiles(&self) -> impl Iterator<Item = &str> {
+        self.state.profiles.keys().map(String::as_str)
+    }
+
+    pub fn sync_progress(&self) -> bool {
+        self.state.profile().sync_progress
+    }
+
+    pub fn flag_enabled(&self, flag: &Flag) -> bool {
+        self.state
+            .profile()
+            .flags
+            .get(flag.reference_name())
+            .copied()
+            .unwrap_or_else(|| flag.default())
+    }
+
+    pub fn optional_observed(&self, continuity_reference: &str, release_reference: &str) -> bool {
+        self.state
+            .profile()
+            .optional
+            .get(continuity_reference)
+            .map_or(false, |optional| optional.contains(release_reference))
+    }
+
+    pub fn content_warning_opted_in(&self, kind: &str) -> bool {
+        self.state.profile().content_warnings.contains(kind)
+    }
+
+    pub fn opted_in_content_warnings(&self) -> &HashSet<String> {
+        &self.state.profile().content_warnings
+    }
+
+    pub fn observed_releases_references<'a>(&self, manifest: &'a Manifest) -> Observed<'a> {
+        let mut observed = HashSet::new();
+        for continuity in manifest.continuities() {
+            let continuity_reference = continuity.reference_name();
+            let main_line = self
+                .active(continuity_reference)
+                .map(|active_release| manifest.releases_up_to(continuity_reference, active_release))
+                .unwrap_or_default()
+                .into_iter()
+                .take_while(|release| release.is_available())
+                .collect::<Vec<_>>();
+            let active_chapter = self.active_chapter(continuity_reference);
+            let last = main_line.len().saturating_sub(1);
+            for (index, release) in main_line.into_iter().enumerate() {
+                let chapter = active_chapter.filter(|_| index == last);
+                observed.extend(release.observed_references(chapter));
+            }
+            let optional = manifest
+                .optional_releases(continuity_reference)
+                .filter(|release| {
+                    self.optional_observed(continuity_reference, release.reference_name())
+                        && release.is_available()
+                        && release
+                            .after()
+                            .map_or(true, |after| observed.contains(after))
+                })
+                .flat_map(|release| release.observed_references(None))
+                .collect::<Vec<_>>();
+            observed.extend(optional);
+        }
+        Observed {
+            releases: observed,
+            flags: manifest
+                .flags()
+                .iter()
+                .filter(|flag| self.flag_enabled(flag))
+                .map(Flag::reference_name)
+                .collect(),
+        }
     }
 }
 
+#[derive(Clone)]
 pub struct ReleaseSwitcherHandle {
     dispatch: Dispatch<State>,
+    manifest: DownloadableResource<Irc<Manifest>>,
 }
 impl ReleaseSwitcherHandle {
     pub fn switch(&self, continuity_reference: String, release_reference: String) {
+        let manifest = self.manifest.clone().opt();
         self.dispatch.reduce(move |state| {
-            state
+            let profile = state.profile_mut();
+            if let Some(manifest) = manifest.filter(|_| profile.sync_progress) {
+                profile.sync_releases(&manifest, &continuity_reference, &release_reference);
+            }
+            profile.chapters.remove(&continuity_reference);
+            profile
                 .releases
                 .insert(continuity_reference, release_reference)
         })
     }
+
+    /// Sets the chapter of the active release the reader is up to, `None`
+    /// marks the whole release as observed
+    pub fn switch_chapter(&self, continuity_reference: String, chapter_reference: Option<String>) {
+        self.dispatch.reduce(move |state| {
+            let chapters = &mut state.profile_mut().chapters;
+            match chapter_reference {
+                Some(chapter_reference) => chapters.insert(continuity_reference, chapter_reference),
+                None => chapters.remove(&continuity_reference),
+            }
+        })
+    }
+
+    /// Shows content at the given release without saving it as progress,
+    /// reverting after `PEEK_DURATION_MILLIS`
+    pub fn peek(&self, continuity_reference: String, release_reference: String) {
+        let dispatch = Dispatch::<PeekState>::new();
+        dispatch.reduce(move |peek| {
+            peek.releases
+                .insert(continuity_reference, release_reference);
+            peek.generation += 1;
+        });
+        let generation = dispatch.get().generation;
+        Timeout::new(PEEK_DURATION_MILLIS, move || {
+            Dispatch::<PeekState>::new().reduce(move |peek| {
+                if peek.generation == generation {
+                    log::debug!("Peek timed out");
+                    peek.enabled = false;
+                    peek.releases.clear();
+                }
+            })
+        })
+        .forget();
+    }
+
+    /// Sets if picking a release should peek at it, disabling this ends any
+    /// peek
+    pub fn set_peek_enabled(&self, enabled: bool) {
+        Dispatch::<PeekState>::new().reduce(move |peek| {
+            peek.enabled = enabled;
+            if !enabled {
+                peek.releases.clear();
+            }
+        })
+    }
+
+    /// Every profile as a short code that can be imported on another device
+    pub fn export_code(&self) -> Result<String, String> {
+        let bytes = rmp_serde::to_vec_named(&*self.dispatch.get()).map_err(|e| e.to_string())?;
+        let binary = bytes.into_iter().map(char::from).collect::<String>();
+        web_sys::window()
+            .ok_or("The window is not available")?
+            .btoa(&binary)
+            .map_err(|_| "Unable to encode the progress code".to_string())
+    }
+
+    /// Every profile as JSON that can be imported on another device
+    pub fn export_json(&self) -> Result<String, String> {
+        serde_json::to_string_pretty(&*self.dispatch.get()).map_err(|e| e.to_string())
+    }
+
+    /// Imports profiles exported by `export_code` or `export_json`, replacing
+    /// any profiles with the same name. Anything that does not exist in the
+    /// manifest is left out, and described in the returned list.
+    pub fn import(&self, exported: &str) -> Result<Vec<String>, String> {
+        let manifest = self
+            .manifest
+            .clone()
+            .opt()
+            .ok_or("The manifest has not loaded")?;
+        let exported = exported.trim();
+        let mut imported = if exported.starts_with('{') {
+            serde_json::from_str::<State>(exported).map_err(|e| e.to_string())?
+        } else {
+            let binary = web_sys::window()
+                .unwrap()
+                .atob(exported)
+                .map_err(|_| "Not a valid progress code")?;
+            let bytes = binary.chars().map(|c| c as u8).collect::<Vec<_>>();
+            rmp_serde::from_slice::<State>(&bytes).map_err(|e| e.to_string())?
+        };
+        let removed = imported
+            .profiles
+            .iter_mut()
+            .flat_map(|(name, profile)| {
+                profile
+                    .reconcile(&manifest)
+                    .into_iter()
+                    .map(move |removed| format!("{name}: {removed}"))
+            })
+            .collect();
+        self.dispatch.reduce(move |state| {
+            state.profiles.append(&mut imported.profiles);
+            state.active_profile = imported.active_profile;
+        });
+        Ok(removed)
+    }
+
+    /// Switches to the profile of the given name, creating it if it does not
+    /// exist
+    pub fn switch_profile(&self, profile: String) {
+        self.set_peek_enabled(false);
+        self.dispatch.reduce(move |state| {
+            state.profiles.entry(profile.clone()).or_default();
+            state.active_profile = profile;
+        })
+    }
+
+    /// Removes the profile of the given name, unless it is the only profile
+    pub fn remove_profile(&self, profile: String) {
+        self.dispatch.reduce(move |state| {
+            if state.profiles.len() <= 1 {
+                return;
+            }
+            state.profiles.remove(&profile);
+            if state.active_profile == profile {
+                state.active_profile = state.profiles.keys().next().unwrap().clone();
+            }
+        })
+    }
+
+    pub fn set_sync_progress(&self, sync_progress: bool) {
+        self.dispatch
+            .reduce(move |state| state.profile_mut().sync_progress = sync_progress)
+    }
+
+    pub fn set_flag(&self, flag_reference: String, enabled: bool) {
+        self.dispatch
+            .reduce(move |state| state.profile_mut().flags.insert(flag_reference, enabled))
+    }
+
+    pub fn set_content_warning_opted_in(&self, kind: String, opted_in: bool) {
+        self.dispatch.reduce(move |state| {
+            let content_warnings = &mut state.profile_mut().content_warnings;
+            if opted_in {
+                content_warnings.insert(kind);
+            } else {
+                content_warnings.remove(&kind);
+            }
+        })
+    }
+
+    pub fn set_optional_observed(
+        &self,
+        continuity_reference: String,
+        release_reference: String,
+        observed: bool,
+    ) {
+        self.dispatch.reduce(move |state| {
+            let optional = state
+                .profile_mut()
+                .optional
+                .entry(continuity_reference)
+                .or_default();
+            if observed {
+                optional.insert(release_reference);
+            } else {
+                optional.remove(&release_reference);
+            }
+        })
+    }
 }
 
 #[hook]
 pub fn use_active_release_switcher() -> ReleaseSwitcherHandle {
+    let manifest = use_manifest();
     ReleaseSwitcherHandle {
         dispatch: Dispatch::new(),
+        manifest,
     }
 }
 
 #[hook]
 pub fn use_active_release_tracker() -> ReleaseTrackerHandle {
     let manifest = use_manifest();
-    let state = use_store_value();
-    ReleaseTrackerHandle { state, manifest }
+    let state = use_store_value::<State>();
+    let location = use_location();
+    let shared_releases = location
+        .and_then(|location| location.query::<PreviewQuery>().ok())
+        .zip(manifest.as_ref().opt())
+        .map(|(query, manifest)| shared_releases(&state, manifest, &query.at))
+        .unwrap_or_default();
+    let peek = use_store_value();
+    ReleaseTrackerHandle {
+        state,
+        manifest,
+        shared_releases,
+        peek,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MANIFEST: &str = r#"
+        title = "Test"
+
+        [[continuities]]
+        reference_name = "books"
+        prefix = "b"
+
+        [[releases.books]]
+        reference_name = "b1"
+
+        [[releases.books]]
+        reference_name = "b2"
+
+        [[releases.books]]
+        reference_name = "side"
+        optional = true
+        after = "b2"
+
+        [[releases.books]]
+        reference_name = "b3"
+    "#;
+
+    fn state_at(release_reference: &str) -> State {
+        let mut state = State::default();
+        state
+            .profile_mut()
+            .releases
+            .insert("books".to_string(), release_reference.to_string());
+        state
+    }
+
+    fn previewed(own: &str, at: &str) -> Option<String> {
+        let manifest = toml::from_str::<Manifest>(MANIFEST).unwrap();
+        shared_releases(&state_at(own), &manifest, at)
+            .remove("books")
+            .map(|shared_release| shared_release.previewed)
+    }
+
+    #[test]
+    fn shared_link_previews_at_the_earlier_progress() {
+        assert_eq!(previewed("b1", "b3").as_deref(), Some("b1"));
+        assert_eq!(previewed("b3", "b2").as_deref(), Some("b2"));
+        assert_eq!(previewed("b2", "b2").as_deref(), Some("b2"));
+    }
+
+    #[test]
+    fn shared_link_ignores_optional_and_unknown_releases() {
+        assert_eq!(previewed("b3", "side"), None);
+        assert_eq!(previewed("b3", "b9"), None);
+    }
+
+    #[test]
+    fn legacy_state_becomes_default_profile() {
+        let state = parse_stored_state(r#"{"releases":{"books":"b3"}}"#).unwrap();
+        assert_eq!(state.active_profile, DEFAULT_PROFILE);
+        assert_eq!(state.profiles.len(), 1);
+        assert_eq!(
+            state.profile().releases.get("books").map(String::as_str),
+            Some("b3")
+        );
+    }
+
+    #[test]
+    fn current_envelope_loads_unchanged() {
+        let mut state = State::default();
+        state
+            .profile_mut()
+            .releases
+            .insert("books".to_string(), "b5".to_string());
+        state.profile_mut().sync_progress = true;
+        state.active_profile = "Second".to_string();
+        state
+            .profile_mut()
+            .flags
+            .insert("spoilers".to_string(), true);
+        let stored = serde_json::to_string(&StoredEnvelope {
+            version: STATE_VERSION,
+            state: &state,
+        })
+        .unwrap();
+        assert!(parse_stored_state(&stored).unwrap() == state);
+    }
+
+    #[test]
+    fn unknown_version_is_an_error() {
+        let stored = format!(
+            r#"{{"version":{},"state":{{"releases":{{}}}}}}"#,
+            STATE_VERSION + 1
+        );
+        assert!(parse_stored_state(&stored).is_err());
+    }
 }
@@ -1,17 +1,20 @@
 use std::borrow::Cow;
 use std::collections::{HashMap, HashSet};
-use std::iter::repeat;
+use std::iter::{once, repeat};
 
 use convert_case::{Case, Casing};
+use gloo_timers::callback::Timeout;
 use serde::Deserialize;
 use wasm_bindgen_futures::spawn_local;
 use yew::prelude::*;
 use yewdux::prelude::*;
 
 use crate::routes::search::tokenize;
-use crate::utils::cond::should_show;
+use crate::states::active_release::reconcile_with_manifest;
+use crate::states::pages::seed_pages;
+use crate::utils::cond::{should_show, Observed};
 use crate::utils::downloadable_resource::DownloadableResource;
-use crate::utils::fetch::fetch_manifest;
+use crate::utils::fetch::{fetch_manifest, retry_delay_millis, versioned_path};
 use crate::utils::irc::Irc;
 
 /// The site manifest file
@@ -19,21 +22,40 @@ use crate::utils::irc::Irc;
 pub struct Manifest {
     /// The title of the website
     title: String,
+    /// The category pages without any categories are placed in
+    unknown_category: Option<String>,
     /// Configuration for each continuity
     #[serde(default)]
     continuities: Vec<Continuity>,
     /// Configuration for each release, keys must be continuity `reference_name`s
     #[serde(default)]
     releases: HashMap<String, Vec<Release>>,
+    /// Configuration for each branch of releases, keys must be continuity
+    /// `reference_name`s
+    #[serde(default)]
+    branches: HashMap<String, Vec<Branch>>,
     /// Configuration for each page, keys must be continuity `reference_name`s
     #[serde(default)]
     pages: HashMap<String, Vec<Page>>,
+    /// Configuration for each category
+    #[serde(default)]
+    categories: Vec<Category>,
+    /// Configuration for each reader flag
+    #[serde(default)]
+    flags: Vec<Flag>,
+    /// Configuration for each kind of content warning
+    #[serde(default)]
+    content_warnings: Vec<ContentWarning>,
 }
 
 impl Manifest {
     pub fn title(&self) -> &str {
         &self.title
     }
+    pub fn unknown_category(&self) -> &str {
+        self.unknown_category.as_deref().unwrap_or("Uncategorized")
+    }
+
     pub fn continuities(&self) -> &[Continuity] {
         &self.continuities
     }
@@ -59,8 +81,138 @@ impl Manifest {
             .unwrap_or(NO_RELEASES)
     }
 
+    pub fn main_releases<'a>(
+        &'a self,
+        continuity_reference: &str,
+    ) -> impl Iterator<Item = &'a Release> + 'a {
+        self.releases(continuity_reference)
+            .iter()
+            .filter(|release| !release.optional)
+    }
+
+    pub fn optional_releases<'a>(
+        &'a self,
+        continuity_reference: &str,
+    ) -> impl Iterator<Item = &'a Release> + 'a {
+        self.releases(continuity_reference)
+            .iter()
+            .filter(|release| release.optional)
+    }
+
+    /// The main line releases up to and including the given release. For a
+    /// release on a branch this is the main line up to where the branch splits,
+    /// followed by the branch up to the release.
+    pub fn releases_up_to(
+        &self,
+        continuity_reference: &str,
+        release_reference: &str,
+    ) -> Vec<&Release> {
+        let branch = self
+            .release(continuity_reference, release_reference)
+            .and_then(Release::branch);
+        let trunk = self
+            .main_releases(continuity_reference)
+            .filter(|release| release.branch().is_none());
+        match branch {
+            None => up_to(trunk, release_reference),
+            Some(branch) => {
+                let mut releases = self
+                    .branch_after(continuity_reference, branch)
+                    .map(|after| up_to(trunk, after))
+                    .unwrap_or_default();
+                releases.extend(up_to(
+                    self.main_releases(continuity_reference)
+                        .filter(|release| release.branch() == Some(branch)),
+                    release_reference,
+                ));
+                releases
+            }
+        }
+    }
+
+    /// The latest release in the target continuity that is equivalent to any
+    /// release observed up to and including the given release.
+    pub fn equivalent_release(
+        &self,
+        continuity_reference: &str,
+        release_reference: &str,
+        target_continuity_reference: &str,
+    ) -> Option<&Release> {
+        self.releases_up_to(continuity_reference, release_reference)
+            .into_iter()
+            .rev()
+            .find_map(|release| {
+                release
+                    .equivalents
+                    .get(target_continuity_reference)
+                    .and_then(|equivalent| self.release(target_continuity_reference, equivalent))
+                    .filter(|equivalent| !equivalent.optional())
+                    .or_else(|| {
+                        self.releases(target_continuity_reference)
+                            .iter()
+                            .rev()
+                            .find(|target_release| {
+                                !target_release.optional()
+                                    && target_release.equivalents.get(continuity_reference)
+                                        == Some(&release.reference_name)
+                            })
+                    })
+            })
+    }
+
+    pub fn has_release_equivalents(&self) -> bool {
+        self.releases
+            .values()
+            .flatten()
+            .any(|release| !release.equivalents.is_empty())
+    }
+
+    pub fn branches(&self, continuity_reference: &str) -> &[Branch] {
+        self.branches
+            .get(continuity_reference)
+            .map(|b| &b[..])
+            .unwrap_or(NO_BRANCHES)
+    }
+
+    pub fn branch(&self, continuity_reference: &str, branch_reference: &str) -> Option<&Branch> {
+        self.branches(continuity_reference)
+            .iter()
+            .find(|branch| &branch.reference_name == branch_reference)
+    }
+
+    pub fn branch_display_name<'a>(
+        &'a self,
+        continuity_reference: &str,
+        branch_reference: &'a str,
+    ) -> Cow<'a, str> {
+        self.branch(continuity_reference, branch_reference)
+            .and_then(|branch| branch.display_name.as_deref())
+            .map(Cow::Borrowed)
+            .unwrap_or_else(|| Cow::Owned(branch_reference.to_case(Case::Title)))
+    }
+
+    /// The main line release a branch splits from, if not configured this is
+    /// the last main line release before the first release of the branch.
+    pub fn branch_after<'a>(
+        &'a self,
+        continuity_reference: &str,
+        branch_reference: &str,
+    ) -> Option<&'a str> {
+        if let Some(after) = self
+            .branch(continuity_reference, branch_reference)
+            .and_then(|branch| branch.after.as_deref())
+        {
+            return Some(after);
+        }
+        self.main_releases(continuity_reference)
+            .take_while(|release| release.branch() != Some(branch_reference))
+            .filter(|release| release.branch().is_none())
+            .last()
+            .map(Release::reference_name)
+    }
+
     pub fn default_release(&self, continuity_reference: &str) -> Option<&Release> {
-        self.releases(continuity_reference).first()
+        self.main_releases(continuity_reference).next()
     }
 
     pub fn all_releases(&self) -> Vec<(&Release, &Continuity)> {
@@ -102,6 +254,94 @@ impl Manifest {
             .iter()
             .find(|page| &page.page_url == page_reference)
     }
+
+    pub fn page_redirect(
+        &self,
+        continuity_reference: &str,
+        page_reference: &str,
+        observed_releases_references: &Observed,
+        continuity_prefix: &str,
+    ) -> Option<&Page> {
+        self.pages(continuity_reference).iter().find(|page| {
+            page.redirect_from(observed_releases_references, continuity_prefix)
+                .contains(&page_reference)
+        })
+    }
+
+    pub fn equivalent_page(
+        &self,
+        continuity_reference: &str,
+        page_reference: &str,
+        target_continuity_reference: &str,
+    ) -> Option<&Page> {
+        self.page(continuity_reference, page_reference)
+            .and_then(|page| page.equivalents.get(target_continuity_reference))
+            .and_then(|equivalent| self.page(target_continuity_reference, equivalent))
+            .or_else(|| self.page(target_continuity_reference, page_reference))
+            .or_else(|| self.default_page(target_continuity_reference))
+    }
+
+    pub fn categories(&self) -> &[Category] {
+        &self.categories
+    }
+
+    pub fn category(&self, name: &str) -> Option<&Category> {
+        self.categories()
+            .iter()
+            .find(|category| &category.name == name)
+    }
+
+    pub fn flags(&self) -> &[Flag] {
+        &self.flags
+    }
+
+    pub fn flag(&self, flag_reference: &str) -> Option<&Flag> {
+        self.flags()
+            .iter()
+            .find(|flag| &flag.reference_name == flag_reference)
+    }
+
+    pub fn content_warnings(&self) -> &[ContentWarning] {
+        &self.content_warnings
+    }
+
+    /// Every kind of content warning that is configured or used by a page, with
+    /// configured kinds first
+    pub fn content_warning_kinds(&self) -> Vec<&str> {
+        let mut kinds = self
+            .content_warnings()
+            .iter()
+            .map(ContentWarning::kind)
+            .collect::<Vec<_>>();
+        let mut page_kinds = self
+            .pages
+            .values()
+            .flatten()
+            .flat_map(Page::content_warnings)
+            .map(String::as_str)
+            .filter(|kind| !kinds.contains(kind))
+            .collect::<Vec<_>>();
+        page_kinds.sort_unstable();
+        page_kinds.dedup();
+        kinds.extend(page_kinds);
+        kinds
+    }
+
+    pub fn content_warning_display_name<'a>(&'a self, kind: &'a str) -> Cow<'a, str> {
+        self.content_warnings()
+            .iter()
+            .find(|content_warning| content_warning.kind == kind)
+            .map(ContentWarning::display_name)
+            .unwrap_or_else(|| Cow::Owned(kind.to_case(Case::Title)))
+    }
+
+    pub fn category_url(&self, name: &str) -> Cow<str> {
+        self.category(name)
+            .and_then(|category| category.url_name.as_deref())
+            .map(Cow::Borrowed)
+            .unwrap_or_else(|| Cow::Owned(name.to_case(Case::Snake)))
+    }
+
     pub fn continuity_from_url_prefix(&self, url_prefix: &str) -> Option<&Continuity> {
         self.continuities()
             .iter()
@@ -120,6 +360,8 @@ pub struct Continuity {
     url_prefix: Option<String>,
     /// The prefix for releases of this continuity
     prefix: String,
+    /// The path of a bundle holding the pages of this continuity
+    bundle: Option<String>,
 }
 
 impl Continuity {
@@ -144,6 +386,10 @@ impl Continuity {
     pub fn prefix(&self) -> &str {
         &self.prefix
     }
+
+    pub fn bundle(&self) -> Option<&str> {
+        self.bundle.as_deref()
+    }
 }
 
 /// Configuration for a single release
@@ -155,6 +401,32 @@ pub struct Release {
     display_name: Option<String>,
     /// An optional group heading this and all releases after should be part of
     begins_group: Option<String>,
+    /// Optional releases are not part of the main line of releases, and can be
+    /// marked as observed independently
+    #[serde(default)]
+    optional: bool,
+    /// The main line release an optional release should be observed after
+    after: Option<String>,
+    /// The `Branch.reference_name` of the branch the release is on, if it is
+    /// not on the shared main line
+    branch: Option<String>,
+    /// Map from `Continuity.reference_name` to the `Release.reference_name` of
+    /// the release that is equivalent to this one in that continuity
+    #[serde(default)]
+    equivalents: HashMap<String, String>,
+    /// The chapters (or episodes, etc.) of the release, in order
+    #[serde(default)]
+    chapters: Vec<Chapter>,
+    /// An ISO 8601 date the release is published on, before which it cannot be
+    /// observed
+    available_from: Option<String>,
+    /// If the release should be hidden from the picker, rather than disabled,
+    /// until it is available
+    #[serde(default)]
+    hide_until_available: bool,
+    /// If the picker should show how long until the release is available
+    #[serde(default)]
+    show_countdown: bool,
 }
 
 impl Release {
@@ -169,6 +441,74 @@ impl Release {
             .unwrap_or_else(|| Cow::Owned(self.reference_name().to_case(Case::Title)))
     }
 
+    pub fn optional(&self) -> bool {
+        self.optional
+    }
+
+    pub fn after(&self) -> Option<&str> {
+        self.after.as_deref()
+    }
+
+    pub fn branch(&self) -> Option<&str> {
+        self.branch.as_deref()
+    }
+
+    /// The time the release is available from, in milliseconds since the unix
+    /// epoch, if it has a valid `available_from` date
+    pub fn available_from(&self) -> Option<f64> {
+        self.available_from
+            .as_deref()
+            .map(js_sys::Date::parse)
+            .filter(|time| !time.is_nan())
+    }
+
+    /// If the release has been published by the readers clock
+    pub fn is_available(&self) -> bool {
+        self.available_from()
+            .map_or(true, |available_from| available_from <= js_sys::Date::now())
+    }
+
+    pub fn hide_until_available(&self) -> bool {
+        self.hide_until_available
+    }
+
+    pub fn show_countdown(&self) -> bool {
+        self.show_countdown
+    }
+
+    pub fn chapters(&self) -> &[Chapter] {
+        &self.chapters
+    }
+
+    pub fn chapter(&self, chapter_reference: &str) -> Option<&Chapter> {
+        self.chapters()
+            .iter()
+            .find(|chapter| &chapter.reference_name == chapter_reference)
+    }
+
+    /// The references of the release and its chapters that have been observed
+    /// if the reader is up to the given chapter, or has observed the whole
+    /// release if it is `None`.
+    pub fn observed_references(&self, active_chapter: Option<&str>) -> Vec<&str> {
+        match active_chapter.filter(|chapter| self.chapter(chapter).is_some()) {
+            Some(active_chapter) => {
+                let mut seen = false;
+                self.chapters()
+                    .iter()
+                    .map(Chapter::reference_name)
+                    .take_while(|chapter| {
+                        let was_seen = seen;
+                        seen = *chapter == active_chapter;
+                        !was_seen
+                    })
+                    .collect()
+            }
+            None => once(self.reference_name())
+                .chain(self.chapters().iter().map(Chapter::reference_name))
+                .collect(),
+        }
+    }
+
     pub fn begins_group(&self) -> Option<Option<&str>> {
         if let Some(group) = self.begins_group.as_deref() {
             if group.is_empty() {
@@ -182,6 +522,194 @@ impl Release {
     }
 }
 
+/// Configuration for a single reader flag, for spoilers that are not tied to
+/// a release such as author interviews or supplementary material
+#[derive(Debug, Deserialize, Eq, PartialEq)]
+pub struct Flag {
+    /// The name to refer to the flag as in conditionals
+    reference_name: String,
+    /// The name to display in the settings
+    display_name: Option<String>,
+    /// A short explanation of the flag to display in the settings
+    description: Option<String>,
+    /// If the flag is enabled for readers who have not set it
+    #[serde(default)]
+    default: bool,
+}
+
+impl Flag {
+    pub fn reference_name(&self) -> &str {
+        &self.reference_name
+    }
+
+    pub fn display_name(&self) -> Cow<str> {
+        self.display_name
+            .as_deref()
+            .map(Cow::Borrowed)
+            .unwrap_or_else(|| Cow::Owned(self.reference_name().to_case(Case::Title)))
+    }
+
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    pub fn default(&self) -> bool {
+        self.default
+    }
+}
+
+/// Configuration for a single kind of content warning
+#[derive(Debug, Deserialize, Eq, PartialEq)]
+pub struct ContentWarning {
+    /// The kind used in `<content-warning kind="...">` and page
+    /// `content_warnings`
+    kind: String,
+    /// The name to display in warnings and the settings
+    display_name: Option<String>,
+}
+
+impl ContentWarning {
+    pub fn kind(&self) -> &str {
+        &self.kind
+    }
+
+    pub fn display_name(&self) -> Cow<str> {
+        self.display_name
+            .as_deref()
+            .map(Cow::Borrowed)
+            .unwrap_or_else(|| Cow::Owned(self.kind().to_case(Case::Title)))
+    }
+}
+
+/// Configuration for a single category
+#[derive(Debug, Deserialize, Eq, PartialEq)]
+pub struct Category {
+    /// The name pages use to refer to the category
+    name: String,
+    /// The path to use in the URL for the category
+    url_name: Option<String>,
+    /// The name to display in the category browser
+    display_name: Option<String>,
+    /// Names that should be displayed instead of `display_name` given a
+    /// condition, the last matching name is used
+    #[serde(default)]
+    display_name_cond: Vec<DisplayNameCond>,
+    /// A description of the category, written in markdown
+    description: Option<String>,
+    /// Categories are ordered by this, and then by their display name
+    #[serde(default)]
+    sort_order: i64,
+    /// The name of the category this is a subcategory of
+    parent: Option<String>,
+    /// Parents that should be used instead of `parent` given a condition, the
+    /// last matching parent is used
+    #[serde(default)]
+    parent_cond: Vec<ParentCond>,
+    /// Hidden categories do not show in the category browser
+    #[serde(default)]
+    hidden: bool,
+}
+
+impl Category {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn display_name(
+        &self,
+        observed_releases_references: &Observed,
+        continuity_prefix: &str,
+    ) -> &str {
+        self.display_name_cond
+            .iter()
+            .rev()
+            .find(|display_name_cond| {
+                display_name_cond
+                    .cond
+                    .iter()
+                    .all(|cond| should_show(observed_releases_references, cond, continuity_prefix))
+            })
+            .map(|display_name_cond| display_name_cond.display_name.as_str())
+            .or(self.display_name.as_deref())
+            .unwrap_or(&self.name)
+    }
+
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    pub fn sort_order(&self) -> i64 {
+        self.sort_order
+    }
+
+    pub fn parent(
+        &self,
+        observed_releases_references: &Observed,
+        continuity_prefix: &str,
+    ) -> Option<&str> {
+        self.parent_cond
+            .iter()
+            .rev()
+            .find(|parent_cond| {
+                parent_cond
+                    .cond
+                    .iter()
+                    .all(|cond| should_show(observed_releases_references, cond, continuity_prefix))
+            })
+            .map(|parent_cond| parent_cond.parent.as_str())
+            .or(self.parent.as_deref())
+    }
+
+    pub fn hidden(&self) -> bool {
+        self.hidden
+    }
+}
+
+/// A conditional parent category
+#[derive(Debug, Deserialize, Eq, PartialEq)]
+pub struct ParentCond {
+    /// The category to be a subcategory of
+    parent: String,
+    /// The conditions that must be met to be a subcategory
+    cond: Vec<String>,
+}
+
+/// Configuration for a single chapter of a release
+#[derive(Debug, Deserialize, Eq, PartialEq)]
+pub struct Chapter {
+    /// The name to refer to the chapter as in conditionals, this should be the
+    /// `reference_name` of the release followed by a `.` and the chapter, i.e.
+    /// `b3.12`
+    reference_name: String,
+    /// The name to display in the picker
+    display_name: Option<String>,
+}
+
+impl Chapter {
+    pub fn reference_name(&self) -> &str {
+        &self.reference_name
+    }
+
+    pub fn display_name(&self) -> Cow<str> {
+        self.display_name
+            .as_deref()
+            .map(Cow::Borrowed)
+            .unwrap_or_else(|| Cow::Owned(self.reference_name().to_case(Case::Title)))
+    }
+}
+
+/// Configuration for a single branch of releases, for stories with mutually
+/// exclusive routes after a shared main line
+#[derive(Debug, Deserialize, Eq, PartialEq)]
+pub struct Branch {
+    /// The name releases use to refer to the branch
+    reference_name: String,
+    /// The name to display in the picker
+    display_name: Option<String>,
+    /// The main line release the branch splits from
+    after: Option<String>,
+}
+
 /// Configuration for a single page
 #[derive(Debug, Deserialize, Eq, PartialEq)]
 pub struct Page {
@@ -189,8 +717,19 @@ pub struct Page {
     page_url: String,
     /// The path to download the pages markdown
     resource_path: String,
+    /// Paths to download the pages markdown from instead of `resource_path`
+    /// given a condition, the last matching path is used
+    #[serde(default)]
+    resource_path_cond: Vec<ResourcePathCond>,
+    /// A hash of the content at `resource_path`, which changes whenever the
+    /// content does so readers never see a stale cached copy
+    content_hash: Option<String>,
     /// The name to display in the title
     display_name: Option<String>,
+    /// Names that should be displayed instead of `display_name` given a
+    /// condition, the last matching name is used
+    #[serde(default)]
+    display_name_cond: Vec<DisplayNameCond>,
     /// Configures conditions which must all be met in order to show the page.
     /// i.e. ["o-b2", "x-b5"] would only show after b2 but before b5.
     #[serde(default)]
@@ -207,13 +746,46 @@ pub struct Page {
     /// Title peers that should only redirect after a certain condition
     #[serde(default)]
     title_peers_cond: Vec<TitlePeerCond>,
+    /// Map from `Continuity.reference_name` to the `page_url` of the page that
+    /// is the same page in that continuity
+    #[serde(default)]
+    equivalents: HashMap<String, String>,
+    /// Previous page URLs that should redirect to this page
+    #[serde(default)]
+    redirect_from: Vec<String>,
+    /// Previous page URLs that should only redirect after a certain condition
+    #[serde(default)]
+    redirect_from_cond: Vec<RedirectFromCond>,
     /// Categories that the page should show in, if none are configured it will
-    /// show as "Uncategorized".
+    /// show in the manifests `unknown_category`.
     #[serde(default)]
     categories: Vec<String>,
     /// Categories that the page should only show in within a specific condition
     #[serde(default)]
     categories_cond: Vec<CategoryCond>,
+    /// Kinds of content warning that apply to the whole page
+    #[serde(default)]
+    content_warnings: Vec<String>,
+}
+
+/// A conditional resource path
+#[derive(Debug, Deserialize, Eq, PartialEq)]
+pub struct ResourcePathCond {
+    /// The path to download the pages markdown
+    resource_path: String,
+    /// A hash of the content at the path
+    content_hash: Option<String>,
+    /// The conditions that must be met to download from the path
+    cond: Vec<String>,
+}
+
+/// A conditional display name
+#[derive(Debug, Deserialize, Eq, PartialEq)]
+pub struct DisplayNameCond {
+    /// The name that should be displayed
+    display_name: String,
+    /// The conditions that must be met to display the name
+    cond: Vec<String>,
 }
 
 /// A conditional keywords set
@@ -234,6 +806,15 @@ pub struct TitlePeerCond {
     cond: Vec<String>,
 }
 
+/// A conditional redirect
+#[derive(Debug, Deserialize, Eq, PartialEq)]
+pub struct RedirectFromCond {
+    /// The page URL which should redirect
+    from: String,
+    /// The conditions that must be met to do the redirect
+    cond: Vec<String>,
+}
+
 /// A conditional category
 #[derive(Debug, Deserialize, Eq, PartialEq)]
 pub struct CategoryCond {
@@ -244,24 +825,85 @@ pub struct CategoryCond {
 }
 
 static NO_RELEASES: &[Release] = &[];
+static NO_BRANCHES: &[Branch] = &[];
 static NO_PAGES: &[Page] = &[];
 
 impl Page {
     pub fn page_url(&self) -> &str {
         &self.page_url
     }
-    pub fn resource_path(&self) -> &str {
-        &self.resource_path
+    fn matching_resource_path_cond(
+        &self,
+        observed_releases_references: &Observed,
+        continuity_prefix: &str,
+    ) -> Option<&ResourcePathCond> {
+        self.resource_path_cond
+            .iter()
+            .rev()
+            .find(|resource_path_cond| {
+                resource_path_cond
+                    .cond
+                    .iter()
+                    .all(|cond| should_show(observed_releases_references, cond, continuity_prefix))
+            })
     }
-    pub fn display_name(&self) -> Cow<str> {
-        self.display_name
-            .as_deref()
+    pub fn resource_path(
+        &self,
+        observed_releases_references: &Observed,
+        continuity_prefix: &str,
+    ) -> &str {
+        self.matching_resource_path_cond(observed_releases_references, continuity_prefix)
+            .map(|resource_path_cond| resource_path_cond.resource_path.as_str())
+            .unwrap_or(&self.resource_path)
+    }
+    /// The hash of the content at the `resource_path` for the observed
+    /// releases, if it is known
+    pub fn content_hash(
+        &self,
+        observed_releases_references: &Observed,
+        continuity_prefix: &str,
+    ) -> Option<&str> {
+        match self.matching_resource_path_cond(observed_releases_references, continuity_prefix) {
+            Some(resource_path_cond) => resource_path_cond.content_hash.as_deref(),
+            None => self.content_hash.as_deref(),
+        }
+    }
+    /// Every path the pages markdown may be downloaded from, whatever the
+    /// observed releases, versioned by their content hash
+    pub fn resource_paths(&self) -> impl Iterator<Item = Cow<str>> {
+        once(versioned_path(
+            &self.resource_path,
+            self.content_hash.as_deref(),
+        ))
+        .chain(self.resource_path_cond.iter().map(|resource_path_cond| {
+            versioned_path(
+                &resource_path_cond.resource_path,
+                resource_path_cond.content_hash.as_deref(),
+            )
+        }))
+    }
+    pub fn display_name(
+        &self,
+        observed_releases_references: &Observed,
+        continuity_prefix: &str,
+    ) -> Cow<str> {
+        self.display_name_cond
+            .iter()
+            .rev()
+            .find(|display_name_cond| {
+                display_name_cond
+                    .cond
+                    .iter()
+                    .all(|cond| should_show(observed_releases_references, cond, continuity_prefix))
+            })
+            .map(|display_name_cond| display_name_cond.display_name.as_str())
+            .or(self.display_name.as_deref())
             .map(Cow::Borrowed)
             .unwrap_or_else(|| Cow::Owned(self.page_url().to_case(Case::Title)))
     }
     pub fn should_show(
         &self,
-        observed_releases_references: &HashSet<&str>,
+        observed_releases_references: &Observed,
         continuity_prefix: &str,
     ) -> bool {
         self.show_cond
@@ -270,7 +912,7 @@ impl Page {
     }
     pub fn keywords(
         &self,
-        observed_releases_references: &HashSet<&str>,
+        observed_releases_references: &Observed,
         continuity_prefix: &str,
     ) -> HashSet<String> {
         tokenize(&self.keywords)
@@ -286,7 +928,7 @@ impl Page {
             )
             .collect()
     }
-    pub fn title_peers(&self, observed_releases_references: &HashSet<&str>, continuity_prefix: &str) -> Vec<String> {
+    pub fn title_peers(&self, observed_releases_references: &Observed, continuity_prefix: &str) -> Vec<String> {
         self.title_peers.clone().into_iter().chain(
             self.title_peers_cond.iter().filter(|title_peer_cond| {
                 title_peer_cond.cond.iter().all(|cond| {
@@ -295,22 +937,140 @@ impl Page {
             }).map(|title_peer_cond| title_peer_cond.peer.clone())
         ).collect()
     }
-    pub fn categories(&self, observed_releases_references: &HashSet<&str>, continuity_prefix: &str) -> Vec<String> {
-        self.categories.clone().into_iter().chain(
-            self.categories_cond.iter().filter(|category_cond| {
-                category_cond.cond.iter().all(|cond| {
-                    should_show(observed_releases_references, cond, continuity_prefix)
-                })
-            }).map(|title_peer_cond| title_peer_cond.category.clone())
-        ).collect()
+    pub fn redirect_from(
+        &self,
+        observed_releases_references: &Observed,
+        continuity_prefix: &str,
+    ) -> Vec<&str> {
+        self.redirect_from
+            .iter()
+            .map(String::as_str)
+            .chain(
+                self.redirect_from_cond
+                    .iter()
+                    .filter(|redirect_from_cond| {
+                        redirect_from_cond.cond.iter().all(|cond| {
+                            should_show(observed_releases_references, cond, continuity_prefix)
+                        })
+                    })
+                    .map(|redirect_from_cond| redirect_from_cond.from.as_str()),
+            )
+            .collect()
+    }
+    pub fn categories(
+        &self,
+        observed_releases_references: &Observed,
+        continuity_prefix: &str,
+        unknown_category: &str,
+    ) -> Vec<String> {
+        let categories = self
+            .categories
+            .clone()
+            .into_iter()
+            .chain(
+                self.categories_cond
+                    .iter()
+                    .filter(|category_cond| {
+                        category_cond.cond.iter().all(|cond| {
+                            should_show(observed_releases_references, cond, continuity_prefix)
+                        })
+                    })
+                    .map(|category_cond| category_cond.category.clone()),
+            )
+            .collect::<Vec<_>>();
+        if categories.is_empty() {
+            vec![unknown_category.to_string()]
+        } else {
+            categories
+        }
+    }
+
+    pub fn content_warnings(&self) -> &[String] {
+        &self.content_warnings
     }
 }
 
+/// Takes releases up to and including the given release
+/// The releases up to and including the referenced release, or none if it is
+/// not one of the releases, so an unknown reference never observes anything.
+fn up_to<'a>(
+    releases: impl Iterator<Item = &'a Release>,
+    release_reference: &str,
+) -> Vec<&'a Release> {
+    let mut releases_up_to = Vec::new();
+    for release in releases {
+        releases_up_to.push(release);
+        if release.reference_name() == release_reference {
+            return releases_up_to;
+        }
+    }
+    Vec::new()
+}
+
 #[derive(Default, Clone, PartialEq, Store)]
 struct State {
     resource: DownloadableResource<Irc<Manifest>>,
 }
 
+/// Downloads and deserializes the manifest, automatically retrying with
+/// backoff if it fails.
+fn download_manifest(dispatch: Dispatch<State>, attempt: u32) {
+    log::debug!("Downloading and deserializing manifest");
+    dispatch.set(State {
+        resource: DownloadableResource::Downloading,
+    });
+    spawn_local(async move {
+        match fetch_manifest().await {
+            Ok((manifest, bundled_pages)) => {
+                log::debug!("Manifest downloaded and deserialized");
+                seed_pages(bundled_pages);
+                reconcile_with_manifest(&manifest);
+                dispatch.set(State {
+                    resource: DownloadableResource::Ready(Irc::new(manifest)),
+                });
+            }
+            Err(e) => {
+                log::error!("Error downloading or deserializing manifest: {e}");
+                let delay = retry_delay_millis(&e, attempt);
+                dispatch.set(State {
+                    resource: DownloadableResource::Failed(e),
+                });
+                if let Some(delay) = delay {
+                    Timeout::new(delay, move || {
+                        if dispatch.get().resource.failed().is_some() {
+                            download_manifest(dispatch, attempt + 1);
+                        }
+                    })
+                    .forget();
+                }
+            }
+        }
+    });
+}
+
+/// Replaces the downloaded manifest if it differs, such as when it has been
+/// edited while authoring, or if it had failed to download.
+pub(crate) fn replace_manifest(manifest: Manifest) {
+    let dispatch = Dispatch::<State>::new();
+    let changed = match &dispatch.get().resource {
+        DownloadableResource::Ready(current) => **current != manifest,
+        DownloadableResource::Failed(_) => true,
+        _ => false,
+    };
+    if changed {
+        log::info!("Manifest changed, reloading it");
+        reconcile_with_manifest(&manifest);
+        dispatch.set(State {
+            resource: DownloadableResource::Ready(Irc::new(manifest)),
+        });
+    }
+}
+
+/// Downloads the manifest again after it has failed to download.
+pub fn retry_manifest() {
+    download_manifest(Dispatch::new(), 0);
+}
+
 /// Downloads the manifest, and returns it, or if it has already been downloaded
 /// simply returns it.
 #[hook]
@@ -320,20 +1080,7 @@ pub fn use_manifest() -> DownloadableResource<Irc<Manifest>> {
     use_effect_with_deps(
         move |_| {
             if !dispatch.get().resource.requested() {
-                log::debug!("Downloading and deserializing manifest");
-                dispatch.set(State {
-                    resource: DownloadableResource::Downloading,
-                });
-                spawn_local(async move {
-                    if let Some(manifest) = fetch_manifest().await {
-                        log::debug!("Manifest downloaded and deserialized");
-                        dispatch.set(State {
-                            resource: DownloadableResource::Ready(Irc::new(manifest)),
-                        });
-                    } else {
-                        log::error!("Error downloading or deserializing manifest");
-                    }
-                });
+                download_manifest(dispatch, 0);
             }
             || ()
         },
@@ -1,53 +1,303 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 
+use gloo_timers::callback::Timeout;
 use wasm_bindgen_futures::spawn_local;
 use yew::prelude::*;
 use yewdux::prelude::*;
 
+use crate::utils::bundle::{fetch_bundle, BundledPage};
 use crate::utils::downloadable_resource::DownloadableResource;
-use crate::utils::fetch::fetch_text;
+use crate::utils::fetch::{fetch_text, retry_delay_millis, versioned_path};
 use crate::utils::irc::Irc;
 
+/// The most prefetches that may be downloading at once, so prefetching never
+/// holds up the pages a reader opens
+const PREFETCH_BUDGET: usize = 2;
+
+/// A bundle of pages being downloaded, or that has been
+#[derive(Clone, PartialEq)]
+enum BundleDownload {
+    /// Holds the versioned resource paths of opened pages waiting on the bundle
+    Downloading(Vec<String>),
+    /// The bundle has been downloaded, or failed to be
+    Done,
+}
+
 #[derive(Default, Clone, PartialEq, Store)]
 struct State {
-    /// Key is resource path, value is downloaded content
+    /// Key is resource path versioned by its content hash, value is downloaded
+    /// content. Pages with conditional resource paths have each variant cached
+    /// under its own path, and each version of a path under its own hash.
     resource: HashMap<String, DownloadableResource<Irc<String>>>,
+    /// Versioned resource paths waiting to be prefetched, soonest first
+    prefetch_queue: VecDeque<String>,
+    /// The number of prefetches currently downloading
+    prefetching: usize,
+    /// Key is bundle path
+    bundles: HashMap<String, BundleDownload>,
+}
+
+/// Adds bundled pages to the downloaded pages, under the content hash they were
+/// bundled with so they are only used if it matches the manifest.
+pub(crate) fn seed_pages(bundled_pages: Vec<BundledPage>) {
+    if bundled_pages.is_empty() {
+        return;
+    }
+    Dispatch::<State>::new().reduce(move |state| {
+        for page in bundled_pages {
+            let resource_path =
+                versioned_path(&page.resource_path, page.content_hash.as_deref()).into_owned();
+            state.resource.insert(
+                resource_path,
+                DownloadableResource::Ready(Irc::new(page.content)),
+            );
+        }
+    });
+}
+
+/// Downloads a bundle of pages for the page that has been opened, downloading
+/// it by itself if it is not in the bundle or the bundle fails.
+fn download_bundle(dispatch: Dispatch<State>, bundle_path: String, resource_path: String) {
+    log::debug!("Downloading bundle {bundle_path}");
+    let bundle_path_ins = bundle_path.clone();
+    dispatch.reduce(move |state| {
+        state
+            .resource
+            .insert(resource_path.clone(), DownloadableResource::Downloading);
+        state.bundles.insert(
+            bundle_path_ins,
+            BundleDownload::Downloading(vec![resource_path]),
+        );
+    });
+    spawn_local(async move {
+        let bundled_pages = match fetch_bundle(&bundle_path).await {
+            Ok(bundle) => {
+                log::debug!("Bundle {bundle_path} downloaded");
+                bundle.pages
+            }
+            Err(e) => {
+                log::warn!(
+                    "Unable to download bundle {bundle_path}, downloading pages individually: {e}"
+                );
+                Vec::new()
+            }
+        };
+        let waiting = match dispatch.get().bundles.get(&bundle_path) {
+            Some(BundleDownload::Downloading(waiting)) => waiting.clone(),
+            _ => Vec::new(),
+        };
+        dispatch.reduce(move |state| {
+            state.bundles.insert(bundle_path, BundleDownload::Done);
+        });
+        seed_pages(bundled_pages);
+        for resource_path in waiting {
+            let bundled = dispatch
+                .get()
+                .resource
+                .get(&resource_path)
+                .map_or(false, DownloadableResource::is_ready);
+            if !bundled {
+                download_page(dispatch.clone(), resource_path, 0);
+            }
+        }
+    });
+}
+
+/// Downloads a page, automatically retrying with backoff if it fails.
+fn download_page(dispatch: Dispatch<State>, resource_path: String, attempt: u32) {
+    log::debug!("Downloading page {resource_path}");
+    let resource_path_ins = resource_path.clone();
+    dispatch.reduce(move |state| {
+        state
+            .resource
+            .insert(resource_path_ins, DownloadableResource::Downloading);
+    });
+    spawn_local(async move {
+        match fetch_text(&resource_path).await {
+            Ok(page) => {
+                log::debug!("Page {resource_path} downloaded");
+                dispatch.reduce(move |state| {
+                    state
+                        .resource
+                        .insert(resource_path, DownloadableResource::Ready(Irc::new(page)))
+                });
+            }
+            Err(e) => {
+                log::error!("Error downloading page {resource_path}: {e}");
+                let delay = retry_delay_millis(&e, attempt);
+                let resource_path_ins = resource_path.clone();
+                dispatch.reduce(move |state| {
+                    state
+                        .resource
+                        .insert(resource_path_ins, DownloadableResource::Failed(e))
+                });
+                if let Some(delay) = delay {
+                    Timeout::new(delay, move || {
+                        let failed = dispatch
+                            .get()
+                            .resource
+                            .get(&resource_path)
+                            .map_or(false, |resource| resource.failed().is_some());
+                        if failed {
+                            download_page(dispatch, resource_path, attempt + 1);
+                        }
+                    })
+                    .forget();
+                }
+            }
+        }
+    });
+}
+
+/// Downloads a page before it is opened, so it is ready when the reader
+/// navigates to it. Failures are not retried, and are forgotten so the page is
+/// downloaded as normal if it is opened.
+fn prefetch_page(dispatch: Dispatch<State>, resource_path: String) {
+    log::debug!("Prefetching page {resource_path}");
+    let resource_path_ins = resource_path.clone();
+    dispatch.reduce(move |state| {
+        state.prefetching += 1;
+        state
+            .resource
+            .insert(resource_path_ins, DownloadableResource::Downloading);
+    });
+    spawn_local(async move {
+        let page = fetch_text(&resource_path).await;
+        dispatch.reduce(move |state| {
+            state.prefetching -= 1;
+            match page {
+                Ok(page) => {
+                    log::debug!("Page {resource_path} prefetched");
+                    state
+                        .resource
+                        .insert(resource_path, DownloadableResource::Ready(Irc::new(page)));
+                }
+                Err(e) => {
+                    log::debug!("Unable to prefetch page {resource_path}: {e}");
+                    state.resource.remove(&resource_path);
+                }
+            }
+        });
+        prefetch_next(dispatch);
+    });
 }
 
+/// Starts prefetching queued pages until the prefetch budget is used up.
+fn prefetch_next(dispatch: Dispatch<State>) {
+    loop {
+        let state = dispatch.get();
+        if state.prefetching >= PREFETCH_BUDGET {
+            return;
+        }
+        let mut prefetch_queue = state.prefetch_queue.clone();
+        // Skip pages that have been opened since they were queued
+        let next = loop {
+            match prefetch_queue.pop_front() {
+                Some(resource_path) if state.resource.contains_key(&resource_path) => continue,
+                next => break next,
+            }
+        };
+        dispatch.reduce(move |state| state.prefetch_queue = prefetch_queue);
+        match next {
+            Some(resource_path) => prefetch_page(dispatch.clone(), resource_path),
+            None => return,
+        }
+    }
+}
+
+/// Queues versioned resource paths to be downloaded before they are opened.
+/// Urgent paths, such as a link being hovered, are prefetched before any
+/// already queued.
+pub fn prefetch_pages(resource_paths: Vec<String>, urgent: bool) {
+    let dispatch = Dispatch::<State>::new();
+    dispatch.reduce(move |state| {
+        let resource_paths = resource_paths
+            .into_iter()
+            .filter(|resource_path| !state.resource.contains_key(resource_path))
+            .collect::<Vec<_>>();
+        state
+            .prefetch_queue
+            .retain(|queued| !resource_paths.contains(queued));
+        if urgent {
+            for resource_path in resource_paths.into_iter().rev() {
+                state.prefetch_queue.push_front(resource_path);
+            }
+        } else {
+            state.prefetch_queue.extend(resource_paths);
+        }
+    });
+    prefetch_next(dispatch);
+}
+
+/// Replaces the content of a downloaded page if it differs, such as when it has
+/// been edited while authoring, or if it had failed to download.
+pub(crate) fn replace_page(resource_path: String, content: String) {
+    let dispatch = Dispatch::<State>::new();
+    let changed = match dispatch.get().resource.get(&resource_path) {
+        Some(DownloadableResource::Ready(current)) => **current != content,
+        Some(DownloadableResource::Failed(_)) => true,
+        _ => false,
+    };
+    if changed {
+        log::info!("Page {resource_path} changed, reloading it");
+        dispatch.reduce(move |state| {
+            state.resource.insert(
+                resource_path,
+                DownloadableResource::Ready(Irc::new(content)),
+            )
+        });
+    }
+}
+
+/// Downloads a page again after it has failed to download.
+pub fn retry_page(resource_path: &str, content_hash: Option<&str>) {
+    download_page(
+        Dispatch::new(),
+        versioned_path(resource_path, content_hash).into_owned(),
+        0,
+    );
+}
+
+/// Gets the content of a page, downloading it if it has not been, from the
+/// bundle of its continuity if there is one.
 #[hook]
-pub fn use_page(resource_path: &str) -> DownloadableResource<Irc<String>> {
+pub fn use_page(
+    resource_path: &str,
+    content_hash: Option<&str>,
+    bundle_path: Option<&str>,
+) -> DownloadableResource<Irc<String>> {
     let (state, dispatch) = use_store::<State>();
-    let resource_path = resource_path.to_string();
 
-    match state
+    let resource_path = versioned_path(resource_path, content_hash);
+    let resource = state
         .resource
-        .get(&resource_path)
+        .get(resource_path.as_ref())
         .cloned()
-        .unwrap_or_default()
-    {
-        DownloadableResource::Downloading => DownloadableResource::Downloading,
-        DownloadableResource::NotYetRequested => {
-            log::debug!("Downloading page {resource_path}");
-            let resource_path_ins = resource_path.clone();
-            dispatch.reduce(move |state| {
-                state
-                    .resource
-                    .insert(resource_path_ins, DownloadableResource::Downloading);
-            });
-            spawn_local(async move {
-                if let Some(page) = fetch_text(&resource_path).await {
-                    log::debug!("Page {resource_path} downloaded");
-                    dispatch.reduce(move |state| {
-                        state
-                            .resource
-                            .insert(resource_path, DownloadableResource::Ready(Irc::new(page)))
-                    });
-                } else {
-                    log::error!("Error downloading or deserializing page {resource_path}");
-                }
-            });
-            DownloadableResource::Downloading
+        .unwrap_or_default();
+    if !resource.requested() {
+        let resource_path = resource_path.into_owned();
+        match bundle_path.map(|bundle_path| (bundle_path, state.bundles.get(bundle_path))) {
+            Some((bundle_path, None)) => {
+                download_bundle(dispatch, bundle_path.to_string(), resource_path)
+            }
+            Some((bundle_path, Some(BundleDownload::Downloading(_)))) => {
+                let bundle_path = bundle_path.to_string();
+                dispatch.reduce(move |state| {
+                    if let Some(BundleDownload::Downloading(waiting)) =
+                        state.bundles.get_mut(&bundle_path)
+                    {
+                        waiting.push(resource_path.clone());
+                    }
+                    state
+                        .resource
+                        .insert(resource_path, DownloadableResource::Downloading);
+                });
+            }
+            Some((_, Some(BundleDownload::Done))) | None => {
+                download_page(dispatch, resource_path, 0)
+            }
         }
-        DownloadableResource::Ready(ready) => DownloadableResource::Ready(ready),
+        return DownloadableResource::Downloading;
     }
+    resource
 }
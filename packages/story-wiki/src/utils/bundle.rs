@@ -0,0 +1,46 @@
+use serde::{Deserialize, Serialize};
+use web_sys::window;
+
+use crate::utils::fetch::{fetch_binary, FetchError};
+
+/// Many files of a site in a single MessagePack file, so they can be
+/// downloaded in one request instead of many.
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct Bundle {
+    /// The content of `manifest.toml`, only read from the bundle advertised by
+    /// the site
+    #[serde(default)]
+    pub manifest: Option<String>,
+    /// The pages in the bundle
+    #[serde(default)]
+    pub pages: Vec<BundledPage>,
+}
+
+/// The markdown of a page in a bundle
+#[derive(Debug, Deserialize, Serialize)]
+pub struct BundledPage {
+    /// The `resource_path` the markdown would otherwise be downloaded from
+    pub resource_path: String,
+    /// The `content_hash` of the markdown, a page is only used if this matches
+    /// the manifest
+    #[serde(default)]
+    pub content_hash: Option<String>,
+    /// The markdown of the page
+    pub content: String,
+}
+
+/// The path of the bundle advertised by the site, set by a
+/// `<meta name="story-wiki-bundle">` tag.
+pub fn advertised_bundle() -> Option<String> {
+    window()?
+        .document()?
+        .query_selector(r#"meta[name="story-wiki-bundle"]"#)
+        .ok()??
+        .get_attribute("content")
+        .filter(|content| !content.is_empty())
+}
+
+pub async fn fetch_bundle(bundle_path: &str) -> Result<Bundle, FetchError> {
+    let bytes = fetch_binary(bundle_path).await?;
+    rmp_serde::from_slice(&bytes).map_err(|e| FetchError::Decode(e.to_string()))
+}
@@ -1,10 +1,15 @@
 use std::collections::HashSet;
 
-pub fn should_show(
-    observed_releases_references: &HashSet<&str>,
-    cond: &str,
-    continuity_prefix: &str,
-) -> bool {
+/// Everything conditions are checked against for a reader
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Observed<'a> {
+    /// The reference names of the releases and chapters the reader has observed
+    pub releases: HashSet<&'a str>,
+    /// The reference names of the flags the reader has enabled
+    pub flags: HashSet<&'a str>,
+}
+
+pub fn should_show(observed: &Observed, cond: &str, continuity_prefix: &str) -> bool {
     let (prefix_mode, tag) = cond
         .split_once('-')
         .expect(&format!("Invalid condition: {cond}"));
@@ -12,20 +17,22 @@ pub fn should_show(
     match prefix_mode {
         "x" => {
             if in_continuity {
-                !observed_releases_references.contains(&tag)
+                !observed.releases.contains(&tag)
             } else {
                 true
             }
         }
         "o" => {
             if in_continuity {
-                observed_releases_references.contains(&tag)
+                observed.releases.contains(&tag)
             } else {
                 true
             }
         }
-        "xx" => !observed_releases_references.contains(&tag),
-        "oo" => observed_releases_references.contains(&tag),
+        "xx" => !observed.releases.contains(&tag),
+        "oo" => observed.releases.contains(&tag),
+        "f" => observed.flags.contains(&tag),
+        "nf" => !observed.flags.contains(&tag),
         _ => panic!("Invalid condition: {cond}"),
     }
 }
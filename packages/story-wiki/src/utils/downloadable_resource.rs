@@ -1,11 +1,14 @@
 use std::fmt::Debug;
 use std::ops::Deref;
 
-#[derive(Debug, Eq, PartialEq, Hash, Copy, Clone)]
+use crate::utils::fetch::FetchError;
+
+#[derive(Debug, Eq, PartialEq, Hash, Clone)]
 pub enum DownloadableResource<T> {
     NotYetRequested,
     Downloading,
     Ready(T),
+    Failed(FetchError),
 }
 impl<T> Default for DownloadableResource<T> {
     fn default() -> Self {
@@ -21,6 +24,14 @@ impl<T> DownloadableResource<T> {
         matches!(self, DownloadableResource::Ready(_))
     }
 
+    pub fn failed(&self) -> Option<&FetchError> {
+        if let DownloadableResource::Failed(e) = self {
+            Some(e)
+        } else {
+            None
+        }
+    }
+
     pub fn opt(self) -> Option<T> {
         if let DownloadableResource::Ready(t) = self {
             Some(t)
@@ -34,6 +45,7 @@ impl<T> DownloadableResource<T> {
             DownloadableResource::NotYetRequested => DownloadableResource::NotYetRequested,
             DownloadableResource::Downloading => DownloadableResource::Downloading,
             DownloadableResource::Ready(t) => DownloadableResource::Ready(t),
+            DownloadableResource::Failed(e) => DownloadableResource::Failed(e.clone()),
         }
     }
 
@@ -45,6 +57,7 @@ impl<T> DownloadableResource<T> {
             DownloadableResource::NotYetRequested => DownloadableResource::NotYetRequested,
             DownloadableResource::Downloading => DownloadableResource::Downloading,
             DownloadableResource::Ready(t) => DownloadableResource::Ready(&**t),
+            DownloadableResource::Failed(e) => DownloadableResource::Failed(e.clone()),
         }
     }
 }
@@ -1,25 +1,185 @@
-use reqwasm::http::{Request, Response};
+use std::borrow::Cow;
+use std::fmt::{Display, Formatter};
+
+use reqwasm::http::{Request, RequestCache, Response};
 use web_sys::{window, Url};
 
 use crate::states::manifest::Manifest;
+use crate::utils::bundle::{advertised_bundle, fetch_bundle, Bundle, BundledPage};
 
-pub async fn fetch_manifest() -> Option<Manifest> {
-    let text = fetch_text("/manifest.toml").await?;
-    let out = toml::from_str(&text);
+/// The number of times a failed download is automatically retried
+const AUTOMATIC_RETRIES: u32 = 3;
+
+/// The delay before the first automatic retry, doubling for each retry after
+const RETRY_BASE_DELAY_MILLIS: u32 = 1000;
+
+/// Why a download failed
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum FetchError {
+    /// The resource does not exist, including when the server responded with
+    /// the site itself as it does for unknown paths
+    NotFound,
+    /// The server responded with an error status code
+    Server(u16),
+    /// The request could not be made, or no response was received
+    Network(String),
+    /// The response could not be read or deserialized
+    Decode(String),
+}
+
+impl FetchError {
+    /// If retrying the download could succeed
+    pub fn is_transient(&self) -> bool {
+        match self {
+            FetchError::Network(_) => true,
+            FetchError::Server(status) => *status >= 500,
+            FetchError::NotFound | FetchError::Decode(_) => false,
+        }
+    }
+}
+
+impl Display for FetchError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FetchError::NotFound => write!(f, "not found"),
+            FetchError::Server(status) => write!(f, "server error ({status})"),
+            FetchError::Network(e) => write!(f, "network error ({e})"),
+            FetchError::Decode(e) => write!(f, "invalid content ({e})"),
+        }
+    }
+}
+
+/// The delay before automatically retrying a download that has failed on the
+/// given attempt, starting from `0`, or `None` if it should not be retried.
+pub fn retry_delay_millis(error: &FetchError, attempt: u32) -> Option<u32> {
+    (error.is_transient() && attempt < AUTOMATIC_RETRIES)
+        .then(|| RETRY_BASE_DELAY_MILLIS * 2u32.pow(attempt))
+}
+
+fn parse_manifest(text: &str) -> Result<Manifest, FetchError> {
+    let out = toml::from_str(text);
     log::trace!("{out:#?}");
-    out.ok()
+    out.map_err(|e| FetchError::Decode(e.to_string()))
 }
 
-pub async fn fetch(fetch_path: &str) -> Option<Response> {
+/// Downloads the manifest, along with any pages bundled with it. If the site
+/// advertises a bundle the manifest is read from it, falling back to
+/// `manifest.toml` if the bundle cannot be used.
+pub async fn fetch_manifest() -> Result<(Manifest, Vec<BundledPage>), FetchError> {
+    if let Some(bundle_path) = advertised_bundle() {
+        match fetch_bundle(&bundle_path).await {
+            Ok(Bundle {
+                manifest: Some(manifest),
+                pages,
+            }) => match parse_manifest(&manifest) {
+                Ok(manifest) => return Ok((manifest, pages)),
+                Err(e) => log::warn!("Unable to use the manifest in bundle {bundle_path}: {e}"),
+            },
+            Ok(_) => log::warn!("Bundle {bundle_path} does not contain a manifest"),
+            Err(e) => log::warn!("Unable to download bundle {bundle_path}: {e}"),
+        }
+    }
+    let text = fetch_text("/manifest.toml").await?;
+    Ok((parse_manifest(&text)?, Vec::new()))
+}
+
+/// Downloads `manifest.toml`, bypassing the HTTP cache, to check for changes
+/// while authoring.
+pub async fn fetch_manifest_uncached() -> Result<Manifest, FetchError> {
+    let text = fetch_text_with_cache("/manifest.toml", RequestCache::NoStore).await?;
+    parse_manifest(&text)
+}
+
+/// Resolves a path against the site root, as set by the base URI of the page
+pub fn resolve_href(path: &str) -> Option<String> {
     let base = window()?.document()?.base_uri().ok().flatten()?;
-    let uri = Url::new_with_base(fetch_path, &base).ok()?;
-    Request::get(&uri.href()).send().await.ok()
+    Url::new_with_base(path, &base).ok().map(|url| url.href())
+}
+
+/// Appends the content hash to a path as a query parameter, so a path is
+/// fetched again rather than from the HTTP cache whenever its content changes
+pub fn versioned_path<'a>(path: &'a str, content_hash: Option<&str>) -> Cow<'a, str> {
+    match content_hash {
+        Some(content_hash) => {
+            let separator = if path.contains('?') { '&' } else { '?' };
+            Cow::Owned(format!("{path}{separator}v={content_hash}"))
+        }
+        None => Cow::Borrowed(path),
+    }
+}
+
+/// If the path is for a HTML document, ignoring any query
+fn is_html_path(path: &str) -> bool {
+    path.split(|c| c == '?' || c == '#')
+        .next()
+        .unwrap_or(path)
+        .ends_with(".html")
+}
+
+pub async fn fetch(fetch_path: &str) -> Result<Response, FetchError> {
+    fetch_with_cache(fetch_path, RequestCache::Default).await
+}
+
+async fn fetch_with_cache(fetch_path: &str, cache: RequestCache) -> Result<Response, FetchError> {
+    let href = resolve_href(fetch_path)
+        .ok_or_else(|| FetchError::Network(format!("invalid path {fetch_path}")))?;
+    let response = Request::get(&href)
+        .cache(cache)
+        .send()
+        .await
+        .map_err(|e| FetchError::Network(e.to_string()))?;
+    match response.status() {
+        404 | 410 => return Err(FetchError::NotFound),
+        status if !response.ok() => return Err(FetchError::Server(status)),
+        _ => {}
+    }
+    let is_html = response
+        .headers()
+        .get("content-type")
+        .map_or(false, |content_type| content_type.starts_with("text/html"));
+    if is_html && !is_html_path(fetch_path) {
+        log::debug!("Received the site instead of {fetch_path}");
+        return Err(FetchError::NotFound);
+    }
+    Ok(response)
+}
+
+/// If the text is a HTML document, which servers that do not set a
+/// content-type respond with for unknown paths as they serve the site itself
+fn is_html_document(text: &str) -> bool {
+    let text = text.trim_start();
+    text.get(.."<!doctype html>".len())
+        .map_or(false, |start| start.eq_ignore_ascii_case("<!doctype html>"))
+        || text.starts_with("<html")
+}
+
+pub async fn fetch_binary(target: &str) -> Result<Vec<u8>, FetchError> {
+    fetch(target)
+        .await?
+        .binary()
+        .await
+        .map_err(|e| FetchError::Decode(e.to_string()))
+}
+
+pub async fn fetch_text(target: &str) -> Result<String, FetchError> {
+    fetch_text_with_cache(target, RequestCache::Default).await
 }
 
-pub async fn fetch_binary(target: &str) -> Option<Vec<u8>> {
-    fetch(target).await?.binary().await.ok()
+/// Downloads text, bypassing the HTTP cache, to check for changes while
+/// authoring.
+pub async fn fetch_text_uncached(target: &str) -> Result<String, FetchError> {
+    fetch_text_with_cache(target, RequestCache::NoStore).await
 }
 
-pub async fn fetch_text(target: &str) -> Option<String> {
-    fetch(target).await?.text().await.ok()
+async fn fetch_text_with_cache(target: &str, cache: RequestCache) -> Result<String, FetchError> {
+    let text = fetch_with_cache(target, cache)
+        .await?
+        .text()
+        .await
+        .map_err(|e| FetchError::Decode(e.to_string()))?;
+    if is_html_document(&text) && !is_html_path(target) {
+        log::debug!("Received the site instead of {target}");
+        return Err(FetchError::NotFound);
+    }
+    Ok(text)
 }
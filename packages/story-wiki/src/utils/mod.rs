@@ -1,5 +1,7 @@
+pub mod bundle;
 pub mod cond;
 pub mod downloadable_resource;
 pub mod fetch;
 pub mod irc;
+pub mod offline;
 pub mod try_html;
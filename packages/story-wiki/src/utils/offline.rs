@@ -0,0 +1,64 @@
+use js_sys::{Array, JsString, Object, Reflect};
+use wasm_bindgen_futures::{spawn_local, JsFuture};
+use web_sys::{window, ServiceWorkerContainer};
+
+use crate::utils::fetch::resolve_href;
+
+/// The service worker container of the browser, if it supports service workers
+fn service_worker_container() -> Option<ServiceWorkerContainer> {
+    let navigator = window()?.navigator();
+    Reflect::has(&navigator, &"serviceWorker".into())
+        .unwrap_or(false)
+        .then(|| navigator.service_worker())
+}
+
+/// Registers the service worker, which caches the site so it can be read
+/// offline.
+pub fn register_service_worker() {
+    let container = match service_worker_container() {
+        Some(container) => container,
+        None => {
+            log::warn!("Service workers are not supported, reading offline is unavailable");
+            return;
+        }
+    };
+    let href = match resolve_href("service-worker.js") {
+        Some(href) => href,
+        None => return,
+    };
+    let registration = container.register(&href);
+    spawn_local(async move {
+        match JsFuture::from(registration).await {
+            Ok(_) => log::debug!("Service worker registered"),
+            Err(e) => log::error!("Unable to register the service worker: {e:?}"),
+        }
+    });
+}
+
+/// Asks the service worker to download and cache every given path, so they can
+/// be read offline. Returns `false` if there is no service worker to ask.
+pub fn make_available_offline<'a>(paths: impl IntoIterator<Item = &'a str>) -> bool {
+    let controller = match service_worker_container().and_then(|container| container.controller()) {
+        Some(controller) => controller,
+        None => return false,
+    };
+    let urls = paths
+        .into_iter()
+        .filter_map(resolve_href)
+        .map(JsString::from)
+        .collect::<Array>();
+    let message = Object::new();
+    let built = Reflect::set(&message, &"type".into(), &"cache-pages".into())
+        .and_then(|_| Reflect::set(&message, &"urls".into(), &urls));
+    if let Err(e) = built {
+        log::error!("Unable to build service worker message: {e:?}");
+        return false;
+    }
+    match controller.post_message(&message) {
+        Ok(()) => true,
+        Err(e) => {
+            log::error!("Unable to message the service worker: {e:?}");
+            false
+        }
+    }
+}